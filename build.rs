@@ -0,0 +1,19 @@
+fn main() {
+    // The C header is written from the code, so it can never disagree with the library. It goes
+    // to OUT_DIR like any build output; a test checks the copy in include/ is the same.
+    #[cfg(feature = "capi")]
+    {
+        println!("cargo:rerun-if-changed=src/ffi.rs");
+        println!("cargo:rerun-if-changed=cbindgen.toml");
+        let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let out_dir = std::env::var("OUT_DIR").unwrap();
+        let config = cbindgen::Config::from_file(format!("{}/cbindgen.toml", crate_dir))
+            .expect("cbindgen.toml is readable");
+        cbindgen::Builder::new()
+            .with_src(format!("{}/src/ffi.rs", crate_dir))
+            .with_config(config)
+            .generate()
+            .expect("the C API can be described in C")
+            .write_to_file(format!("{}/pandemic.h", out_dir));
+    }
+}
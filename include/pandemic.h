@@ -0,0 +1,95 @@
+#ifndef PANDEMIC_H
+#define PANDEMIC_H
+
+/* Written by build.rs with cbindgen from src/ffi.rs. Do not edit by hand: copy it from the build instead. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// How many action ids there are. New kinds of actions only ever get ids after the last one.
+// Giving and taking a card name the other player by how many seats they sit after the one
+// acting, so those ids pick a different player depending on whose turn it is.
+#define PANDEMIC_ACTIONS 598
+
+// What `pandemic_apply` returns when the library itself went wrong rather than the action.
+#define PANDEMIC_INTERNAL_ERROR -2
+
+// A game for programs written in other languages, through the C API in the generated
+// `pandemic.h`. Strings handed out are the caller's to free with `pandemic_string_free`, and
+// when a call fails, `pandemic_last_error` says why. A game may move between threads but
+// must not be used by two at once.
+typedef struct PandemicGame PandemicGame;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Why the last call on this thread failed. The text stays valid until the next failing call.
+const char *pandemic_last_error(void);
+
+// Deals a new game. `players` is a comma-separated list of names, each with an optional role
+// after a colon, like `"Ana:Medic,Bo"`. `difficulty` may be NULL for the standard game.
+// Returns NULL when the players or the difficulty are not valid.
+//
+// # Safety
+//
+// `players` and `difficulty` are each NULL or a NUL-terminated string.
+struct PandemicGame *pandemic_new(const char *players, const char *difficulty, uint64_t seed);
+
+// Frees a game from `pandemic_new`. NULL is ignored.
+//
+// # Safety
+//
+// `game` is NULL or came from `pandemic_new`, has not been freed yet and is not used again.
+void pandemic_free(struct PandemicGame *game);
+
+// Frees a string from this library. NULL is ignored.
+//
+// # Safety
+//
+// `text` is NULL or a string this library handed out, not freed yet and not used again.
+void pandemic_string_free(char *text);
+
+// Takes action `action` for the player the game is waiting on. Returns 0 when it was played,
+// -1 when it is not legal now or `game` is NULL, and `PANDEMIC_INTERNAL_ERROR` when the
+// library failed; the game should not be used after that.
+//
+// # Safety
+//
+// `game` is NULL or a live game from `pandemic_new` that no other thread is using.
+int32_t pandemic_apply(struct PandemicGame *game, uint32_t action);
+
+// Writes the ids of the legal actions, lowest first, into `actions`, up to `capacity` of
+// them, and returns how many there are in all. None are legal once the game is over, and
+// when `game` is NULL or the library failed it returns 0.
+//
+// # Safety
+//
+// `game` is NULL or a live game from `pandemic_new`. `actions` is NULL or has room for
+// `capacity` ids.
+size_t pandemic_legal_actions(const struct PandemicGame *game, uint32_t *actions, size_t capacity);
+
+// Action `action` as a JSON array of its kind and arguments, like `["drive", "Chicago"]`,
+// with cities, colors and events by their names in the code whatever the language. NULL
+// when it means nothing for the player the game is waiting on.
+//
+// # Safety
+//
+// `game` is NULL or a live game from `pandemic_new`.
+char *pandemic_action_json(const struct PandemicGame *game, uint32_t action);
+
+// The whole game as JSON, the same object the agent mode sends as `state`.
+//
+// # Safety
+//
+// `game` is NULL or a live game from `pandemic_new`.
+char *pandemic_state_json(const struct PandemicGame *game);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* PANDEMIC_H */
@@ -0,0 +1,317 @@
+use std::io::{self, BufRead, Write};
+
+use crate::{
+    board::{self, Cities},
+    cli::Options,
+    command::{self, Command},
+    common::Color,
+    game::{self, Game, Outcome, Phase},
+    i18n::tr,
+    json::{self, Json},
+    player_card::PlayerCard,
+    setup::{self, Difficulty},
+};
+
+fn city(city: Cities) -> Json {
+    format!("{:?}", city).into()
+}
+
+fn color(color: Color) -> Json {
+    format!("{:?}", color).into()
+}
+
+fn card(card: &PlayerCard) -> Json {
+    match card {
+        PlayerCard::CityCard(held) => Json::object([
+            ("type", "city".into()),
+            ("city", city(held.city)),
+            ("color", color(held.color)),
+        ]),
+        PlayerCard::EpidemicCard => Json::object([("type", "epidemic".into())]),
+        PlayerCard::EventCard(event) => Json::object([
+            ("type", "event".into()),
+            ("event", format!("{:?}", event.event()).into()),
+        ]),
+    }
+}
+
+fn outcome(outcome: Outcome) -> Json {
+    format!("{:?}", outcome).to_lowercase().into()
+}
+
+/// A legal action as data: its id, its kind and arguments by their language-independent names,
+/// and the same command as it would be typed.
+fn action(id: usize, command: &Command) -> Json {
+    let (kind, mut fields): (&str, Vec<(&str, Json)>) = match command {
+        Command::Pass => ("pass", vec![]),
+        Command::Drive(to) => ("drive", vec![("city", city(*to))]),
+        Command::Fly(to) => ("fly", vec![("city", city(*to))]),
+        Command::Charter(to) => ("charter", vec![("city", city(*to))]),
+        Command::Shuttle(to) => ("shuttle", vec![("city", city(*to))]),
+        Command::Travel(to) => ("travel", vec![("city", city(*to))]),
+        Command::Build(from) => ("build", vec![("moved_from", from.map(city).into())]),
+        Command::Treat(treated) => ("treat", vec![("color", treated.map(color).into())]),
+        Command::Give(card, player) => (
+            "give",
+            vec![("city", city(*card)), ("player", player.as_str().into())],
+        ),
+        Command::Take(card, player) => (
+            "take",
+            vec![("city", city(*card)), ("player", player.as_str().into())],
+        ),
+        Command::Cure(cured) => ("cure", vec![("color", color(*cured))]),
+        Command::Help(topic) => ("help", vec![("topic", topic.as_str().into())]),
+        Command::Log => ("log", vec![]),
+        Command::Status => ("status", vec![]),
+        Command::Accept => ("accept", vec![]),
+        Command::Decline => ("decline", vec![]),
+        Command::Discard(card) => ("discard", vec![("city", city(*card))]),
+        Command::Play(event) => ("play", vec![("event", format!("{:?}", event).into())]),
+        Command::DiscardEvent(event) => ("discard", vec![("event", format!("{:?}", event).into())]),
+        Command::Say(text) => ("say", vec![("text", text.as_str().into())]),
+        Command::Note(target, text) => (
+            "note",
+            vec![
+                ("target", target.as_str().into()),
+                ("text", text.as_str().into()),
+            ],
+        ),
+    };
+    fields.insert(0, ("id", id.into()));
+    fields.insert(1, ("type", kind.into()));
+    fields.push(("command", command.to_string().into()));
+    Json::object(fields)
+}
+
+/// Everything public about the game, plus every hand: the agents may be playing all seats.
+pub fn state(game: &Game) -> Json {
+    let board = &game.board;
+    let (phase, offer) = match game.phase {
+        Phase::Actions => ("actions", Json::Null),
+        Phase::Offer { to, card, giving } => (
+            "offer",
+            Json::object([
+                ("from", game.current.into()),
+                ("to", to.into()),
+                ("city", city(card)),
+                ("giving", giving.into()),
+            ]),
+        ),
+        Phase::Discard { .. } => ("discard", Json::Null),
+        Phase::Over(_) => ("over", Json::Null),
+    };
+    let diseases = Color::ALL.into_iter().map(|disease| {
+        (
+            format!("{:?}", disease),
+            Json::object([
+                (
+                    "state",
+                    format!("{:?}", board.disease_state(disease))
+                        .to_lowercase()
+                        .into(),
+                ),
+                (
+                    "cubes_left",
+                    (board::MAX_INFECTION_PER_TYPE - board.total_cubes(disease)).into(),
+                ),
+            ]),
+        )
+    });
+    let cities = Cities::ALL.iter().map(|name| {
+        let on_board = &board.map[name];
+        Json::object([
+            ("city", city(*name)),
+            ("color", color(on_board.color)),
+            ("station", on_board.has_research_station.into()),
+            (
+                "cubes",
+                Json::object(Color::ALL.into_iter().map(|disease| {
+                    (
+                        format!("{:?}", disease),
+                        on_board.infection_count(disease).into(),
+                    )
+                })),
+            ),
+        ])
+    });
+    let players = game.players.iter().enumerate().map(|(seat, player)| {
+        Json::object([
+            ("seat", seat.into()),
+            ("name", player.plain_name().into()),
+            ("role", player.role().key().into()),
+            ("location", city(player.location())),
+            ("hand", Json::Array(player.hand.iter().map(card).collect())),
+        ])
+    });
+    Json::object([
+        ("phase", phase.into()),
+        ("waiting_on", game.waiting_on().into()),
+        ("current", game.current.into()),
+        ("actions_left", game.actions_left.into()),
+        (
+            "outcome",
+            match game.phase {
+                Phase::Over(over) => outcome(over),
+                _ => Json::Null,
+            },
+        ),
+        ("offer", offer),
+        ("outbreaks", board.outbreaks.into()),
+        ("infection_rate", board.infection_rate().into()),
+        ("epidemics", board.epidemics.into()),
+        ("epidemics_remaining", board.epidemics_remaining().into()),
+        ("player_deck", board.player_deck.0.len().into()),
+        ("diseases", Json::object(diseases)),
+        ("cities", Json::Array(cities.collect())),
+        ("players", Json::Array(players.collect())),
+        (
+            "infection_discard",
+            Json::Array(
+                board
+                    .infection_discard
+                    .0
+                    .iter()
+                    .rev()
+                    .map(|infection| city(infection.city))
+                    .collect(),
+            ),
+        ),
+        (
+            "player_discard",
+            Json::Array(board.player_discard.0.iter().rev().map(card).collect()),
+        ),
+    ])
+}
+
+/// The legal actions, numbered the way `{"action": N}` picks them.
+pub fn legal_actions(game: &Game) -> Json {
+    Json::Array(
+        game.legal_actions()
+            .iter()
+            .enumerate()
+            .map(|(id, command)| action(id, command))
+            .collect(),
+    )
+}
+
+/// Reads one request: `{"action": N}` picks from the legal actions, and
+/// `{"command": "drive Chicago"}` is typed like at the prompt, for the seat given by `"seat"`
+/// or else the one the game is waiting on. The legal actions are only ever the awaited seat's,
+/// so an action sent with any other `"seat"` is refused rather than played for someone else.
+pub fn request(game: &Game, line: &str) -> Result<(usize, Command), String> {
+    let request = json::parse(line).map_err(|error| tr!("agent.invalid_json", error))?;
+    let seat = match request.get("seat") {
+        Some(seat) => seat
+            .as_usize()
+            .filter(|seat| *seat < game.players.len())
+            .ok_or_else(|| tr!("agent.invalid_seat"))?,
+        None => game.waiting_on().unwrap_or(game.current),
+    };
+    if let Some(id) = request.get("action") {
+        let waiting = game.waiting_on().unwrap_or(game.current);
+        if seat != waiting {
+            return Err(tr!("agent.action_for_other_seat", waiting));
+        }
+        let legal = game.legal_actions();
+        let command = id
+            .as_usize()
+            .and_then(|id| legal.get(id))
+            .ok_or_else(|| tr!("agent.invalid_action", legal.len()))?;
+        return Ok((waiting, command.clone()));
+    }
+    match request.get("command").and_then(Json::as_str) {
+        Some(text) => Ok((seat, command::parse(text)?)),
+        None => Err(tr!("agent.missing_action")),
+    }
+}
+
+fn send(out: &mut impl Write, message: &Json) -> io::Result<()> {
+    writeln!(out, "{}", message)?;
+    out.flush()
+}
+
+/// Plays a game over stdin and stdout, one JSON object per line, for bots. Nothing but those
+/// objects is written to stdout.
+pub fn run(options: &Options) -> io::Result<()> {
+    game::set_quiet(true);
+    let mut out = io::stdout().lock();
+    let Some(players) = &options.players else {
+        let error = Json::object([
+            ("type", "error".into()),
+            ("message", tr!("agent.players_required").into()),
+        ]);
+        return send(&mut out, &error);
+    };
+    let setup = setup::deal_roles(players, options.difficulty.unwrap_or(Difficulty::Standard));
+    let mut game = Game::new(&setup);
+    let mut logged = 0;
+    let mut lines = io::stdin().lock().lines();
+    loop {
+        let entries = &game.board.log.entries()[logged..];
+        logged += entries.len();
+        let observation = Json::object([
+            ("type", "observation".into()),
+            ("state", state(&game)),
+            (
+                "events",
+                Json::array(entries.iter().map(|entry| entry.to_string())),
+            ),
+            ("legal_actions", legal_actions(&game)),
+        ]);
+        send(&mut out, &observation)?;
+        if let Phase::Over(_) = game.phase {
+            return Ok(());
+        }
+
+        loop {
+            let Some(line) = lines.next() else {
+                return Ok(());
+            };
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let applied =
+                request(&game, &line).and_then(|(seat, command)| game.apply(seat, &command));
+            match applied {
+                Ok(()) => break,
+                Err(message) => send(
+                    &mut out,
+                    &Json::object([("type", "error".into()), ("message", message.into())]),
+                )?,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game() -> Game {
+        game::set_quiet(true);
+        let players = [("Ana".to_owned(), None), ("Bo".to_owned(), None)];
+        Game::new(&setup::deal_roles(&players, Difficulty::Introductory))
+    }
+
+    #[test]
+    fn an_action_is_played_for_the_seat_the_game_waits_on() {
+        let game = game();
+        let waiting = game.waiting_on().unwrap();
+        let (seat, command) = request(&game, r#"{"action": 0}"#).unwrap();
+        assert_eq!((seat, command), (waiting, game.legal_actions()[0].clone()));
+        let line = format!(r#"{{"action": 0, "seat": {}}}"#, waiting);
+        assert_eq!(request(&game, &line).unwrap().0, waiting);
+    }
+
+    #[test]
+    fn an_action_for_another_seat_is_refused() {
+        let game = game();
+        let other = (game.waiting_on().unwrap() + 1) % game.players.len();
+        let line = format!(r#"{{"action": 0, "seat": {}}}"#, other);
+        assert!(request(&game, &line).is_err());
+        // A typed command still names its own seat.
+        let line = format!(r#"{{"command": "say hi", "seat": {}}}"#, other);
+        assert_eq!(request(&game, &line).unwrap().0, other);
+    }
+}
@@ -3,7 +3,12 @@ use std::collections::HashSet;
 
 use crate::common::Color;
 use crate::deck::Deck;
+use crate::epidemic_timing::EpidemicTiming;
+use crate::game::say;
+use crate::i18n::{text, tr};
 use crate::infection_card::InfectionCard;
+use crate::infection_risk::InfectionKnowledge;
+use crate::log::{Entry, GameLog};
 use crate::player_card::PlayerCard;
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
@@ -103,6 +108,24 @@ impl City {
             current_outbreak: false,
         }
     }
+
+    pub fn infection_count(&self, color: Color) -> u8 {
+        match color {
+            Color::Blue => self.blue_infection_count,
+            Color::Yellow => self.yellow_infection_count,
+            Color::Black => self.black_infection_count,
+            Color::Red => self.red_infection_count,
+        }
+    }
+
+    pub fn set_infection_count(&mut self, color: Color, count: u8) {
+        match color {
+            Color::Blue => self.blue_infection_count = count,
+            Color::Yellow => self.yellow_infection_count = count,
+            Color::Black => self.black_infection_count = count,
+            Color::Red => self.red_infection_count = count,
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
@@ -126,6 +149,7 @@ pub struct Board {
     pub(crate) player_discard: Deck<PlayerCard>,
     pub(crate) infection_deck: Deck<InfectionCard>,
     pub(crate) infection_discard: Deck<InfectionCard>,
+    pub(crate) infection_knowledge: InfectionKnowledge,
     pub(crate) blue_disease: DiseaseState,
     pub(crate) yellow_disease: DiseaseState,
     pub(crate) black_disease: DiseaseState,
@@ -133,7 +157,9 @@ pub struct Board {
     pub(crate) outbreaks: u8,
     pub(crate) epidemics: u8,
     max_epidemics: u8,
+    epidemic_timing: EpidemicTiming,
     infection_rate: usize,
+    pub(crate) log: GameLog,
 }
 
 impl Board {
@@ -144,12 +170,16 @@ impl Board {
             cities.insert(city.city, city);
         }
 
+        let infection_deck = Deck::<InfectionCard>::new();
+        let infection_knowledge = InfectionKnowledge::new(&infection_deck);
+
         Self {
             map: cities,
             player_deck: Deck::<PlayerCard>::new(),
             player_discard: Deck::new_empty(),
-            infection_deck: Deck::<InfectionCard>::new(),
+            infection_deck,
             infection_discard: Deck::new_empty(),
+            infection_knowledge,
             blue_disease: DiseaseState::Default,
             yellow_disease: DiseaseState::Default,
             black_disease: DiseaseState::Default,
@@ -157,15 +187,14 @@ impl Board {
             outbreaks: 0,
             epidemics: 0,
             max_epidemics: 0,
+            epidemic_timing: EpidemicTiming::default(),
             infection_rate: 0,
+            log: GameLog::default(),
         }
     }
 
     pub fn adjacent_to(&self, city: Cities) -> Option<HashSet<Cities>> {
-        match self.map.get(&city) {
-            Some(city) => Some(city.adjacent_cities.clone()),
-            None => None,
-        }
+        self.map.get(&city).map(|city| city.adjacent_cities.clone())
     }
 
     pub fn is_adjacent(&self, from: Cities, to: Cities) -> bool {
@@ -181,35 +210,50 @@ impl Board {
     pub fn all_cities(&self) -> Vec<Cities> {
         let mut cities = Vec::new();
         for key in self.map.keys() {
-            cities.push(key.clone());
+            cities.push(*key);
         }
         cities
     }
 
     pub fn add_epidemic_cards(&mut self, epidemics: u8) {
         self.max_epidemics = epidemics;
-        self.player_deck.add_epidemic_cards(self.max_epidemics)
+        self.epidemic_timing =
+            EpidemicTiming::new(self.player_deck.add_epidemic_cards(self.max_epidemics));
+    }
+
+    pub fn epidemics_remaining(&self) -> u8 {
+        self.max_epidemics.saturating_sub(self.epidemics)
     }
 
+    /// Chance that the next Draw Cards step turns up at least one Epidemic card.
+    pub fn epidemic_chance(&self) -> f64 {
+        self.epidemic_timing.epidemic_chance(
+            self.player_deck.len(),
+            self.epidemics,
+            DRAW_CARDS_PER_ROUND as usize,
+        )
+    }
+
+    /// Cubes of `color` on the board, including those spread to other-colored cities by outbreaks.
     pub fn total_cubes(&self, color: Color) -> u8 {
-        // println!("Entered total_cubes\ncolor: {:?}", color);
         let mut acc = 0;
         for city in self.map.values() {
-            // println!("city: {:?}", city);
-            if city.color == color {
-                let count = match color {
-                    Color::Blue => city.blue_infection_count,
-                    Color::Yellow => city.yellow_infection_count,
-                    Color::Black => city.black_infection_count,
-                    Color::Red => city.red_infection_count,
-                };
-                // println!("count: {}", count);
-                acc += count
-            }
+            acc += city.infection_count(color);
         }
         acc
     }
 
+    pub fn research_stations(&self) -> Vec<Cities> {
+        let mut cities = Vec::new();
+        for city in self.map.values() {
+            if city.has_research_station {
+                cities.push(city.city);
+            }
+        }
+        cities.sort_unstable();
+        cities
+    }
+
     pub fn total_research_stations(&self) -> u8 {
         let mut acc = 0;
         for city in self.map.values() {
@@ -259,6 +303,7 @@ impl Board {
                 Color::Black => city_obj.black_infection_count += 1,
                 Color::Red => city_obj.red_infection_count += 1,
             };
+            self.log.record(Entry::Infected(city, city_color));
             true
         }
     }
@@ -303,12 +348,13 @@ impl Board {
                 Color::Black => city_obj.black_infection_count += 1,
                 Color::Red => city_obj.red_infection_count += 1,
             };
+            self.log.record(Entry::Infected(city, color));
             true
         }
     }
 
     pub fn outbreak_city(&mut self, city: Cities) -> bool {
-        let mut city = self.map.get_mut(&city).unwrap();
+        let city = self.map.get_mut(&city).unwrap();
         match &city.color {
             Color::Blue => {
                 if self.blue_disease == DiseaseState::Eradicated {
@@ -334,14 +380,18 @@ impl Board {
         if city.current_outbreak {
             return true;
         }
-        println!(
-            "{} had an outbreak! {}/{}",
-            &city.city, self.outbreaks, MAX_OUTBREAKS
+        say!(
+            "{}",
+            tr!("board.outbreak", city.city, self.outbreaks, MAX_OUTBREAKS)
         );
         city.current_outbreak = true;
-        let city_color = city.color.clone();
-        let adjacent_cities = city.adjacent_cities.clone();
+        let city_color = city.color;
+        let outbreak = Entry::Outbreak(city.city, city_color);
+        // Sorted so a seeded game spreads in the same order every time.
+        let mut adjacent_cities: Vec<Cities> = city.adjacent_cities.iter().copied().collect();
+        adjacent_cities.sort();
         self.outbreaks += 1;
+        self.log.record(outbreak);
         if self.outbreaks > MAX_OUTBREAKS {
             false
         } else {
@@ -376,17 +426,27 @@ impl Board {
                 }
             }
         }
-        let mut city = self.map.get_mut(&city).unwrap();
+        let city = self.map.get_mut(&city).unwrap();
         if city.current_outbreak {
             return true;
         }
-        println!(
-            "{} had an outbreak of {}! {}/{}",
-            &city.city, color, self.outbreaks, MAX_OUTBREAKS
+        say!(
+            "{}",
+            tr!(
+                "board.outbreak_of",
+                city.city,
+                color,
+                self.outbreaks,
+                MAX_OUTBREAKS
+            )
         );
         city.current_outbreak = true;
-        let adjacent_cities = city.adjacent_cities.clone();
+        let outbreak = Entry::Outbreak(city.city, color);
+        // Sorted so a seeded game spreads in the same order every time.
+        let mut adjacent_cities: Vec<Cities> = city.adjacent_cities.iter().copied().collect();
+        adjacent_cities.sort();
         self.outbreaks += 1;
+        self.log.record(outbreak);
         if self.outbreaks > MAX_OUTBREAKS {
             false
         } else {
@@ -398,14 +458,71 @@ impl Board {
         }
     }
 
+    /// Ends an infection step. A city outbreaks at most once in each chain reaction, but may
+    /// outbreak again in a later one.
+    pub fn end_infection_step(&mut self) {
+        for city in self.map.values_mut() {
+            city.current_outbreak = false;
+        }
+    }
+
     pub fn increase_infection_rate(&mut self) -> bool {
         self.infection_rate += 1;
         self.infection_rate < INFECTION_RATE.len()
     }
 
+    pub fn infection_rate_position(&self) -> usize {
+        self.infection_rate
+    }
+
     pub fn infection_rate(&self) -> u8 {
         INFECTION_RATE[self.infection_rate]
     }
+
+    pub fn disease_state(&self, color: Color) -> DiseaseState {
+        match color {
+            Color::Blue => self.blue_disease,
+            Color::Yellow => self.yellow_disease,
+            Color::Black => self.black_disease,
+            Color::Red => self.red_disease,
+        }
+    }
+
+    pub fn all_cured(&self) -> bool {
+        [Color::Blue, Color::Yellow, Color::Black, Color::Red]
+            .iter()
+            .all(|color| self.disease_state(*color) != DiseaseState::Default)
+    }
+
+    pub fn set_disease_state(&mut self, color: Color, state: DiseaseState) {
+        match color {
+            Color::Blue => self.blue_disease = state,
+            Color::Yellow => self.yellow_disease = state,
+            Color::Black => self.black_disease = state,
+            Color::Red => self.red_disease = state,
+        }
+    }
+
+    pub fn draw_infection_card(&mut self) -> Option<InfectionCard> {
+        let card = self.infection_deck.draw_from_top()?;
+        self.infection_knowledge.draw_from_top(card.city);
+        Some(card)
+    }
+
+    pub fn draw_epidemic_infection_card(&mut self) -> Option<InfectionCard> {
+        let card = self.infection_deck.draw_from_bottom()?;
+        self.infection_knowledge.draw_from_bottom(card.city);
+        Some(card)
+    }
+
+    /// Shuffles the infection discard pile and places it on top of the infection deck.
+    /// Everyone at the table knows which cities are now on top, just not in which order.
+    pub fn intensify(&mut self) {
+        self.infection_discard.shuffle();
+        self.infection_knowledge
+            .place_on_top(&self.infection_discard);
+        self.infection_deck.append(&mut self.infection_discard);
+    }
 }
 
 fn make_cities() -> Vec<City> {
@@ -854,64 +971,148 @@ fn make_red_cities() -> Vec<City> {
 impl std::fmt::Display for DiseaseState {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::Default => write!(f, "Default"),
-            Self::Cured => write!(f, "Cured"),
-            Self::Eradicated => write!(f, "Eradicated"),
+            Self::Default => write!(f, "{}", tr!("disease.default")),
+            Self::Cured => write!(f, "{}", tr!("disease.cured")),
+            Self::Eradicated => write!(f, "{}", tr!("disease.eradicated")),
+        }
+    }
+}
+
+impl Cities {
+    pub const ALL: [Cities; 48] = [
+        Cities::Algiers,
+        Cities::Atlanta,
+        Cities::Baghdad,
+        Cities::Bangkok,
+        Cities::Beijing,
+        Cities::Bogota,
+        Cities::BuenosAires,
+        Cities::Cairo,
+        Cities::Chennai,
+        Cities::Chicago,
+        Cities::Delhi,
+        Cities::Essen,
+        Cities::HoChiMinhCity,
+        Cities::HongKong,
+        Cities::Istanbul,
+        Cities::Jakarta,
+        Cities::Johannesburg,
+        Cities::Karachi,
+        Cities::Khartoum,
+        Cities::Kinshasa,
+        Cities::Kolkata,
+        Cities::Lagos,
+        Cities::Lima,
+        Cities::London,
+        Cities::LosAngeles,
+        Cities::Madrid,
+        Cities::Manila,
+        Cities::MexicoCity,
+        Cities::Miami,
+        Cities::Milan,
+        Cities::Montreal,
+        Cities::Moscow,
+        Cities::Mumbai,
+        Cities::NewYork,
+        Cities::Osaka,
+        Cities::Paris,
+        Cities::Riyadh,
+        Cities::SaintPetersburg,
+        Cities::SanFrancisco,
+        Cities::Santiago,
+        Cities::SaoPaulo,
+        Cities::Seoul,
+        Cities::Shanghai,
+        Cities::Sydney,
+        Cities::Taipei,
+        Cities::Tehran,
+        Cities::Tokyo,
+        Cities::Washington,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        text(&format!("city.{:?}", self))
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            Self::Algiers => Color::Black,
+            Self::Atlanta => Color::Blue,
+            Self::Baghdad => Color::Black,
+            Self::Bangkok => Color::Red,
+            Self::Beijing => Color::Red,
+            Self::Bogota => Color::Yellow,
+            Self::BuenosAires => Color::Yellow,
+            Self::Cairo => Color::Black,
+            Self::Chennai => Color::Black,
+            Self::Chicago => Color::Blue,
+            Self::Delhi => Color::Black,
+            Self::Essen => Color::Blue,
+            Self::HoChiMinhCity => Color::Red,
+            Self::HongKong => Color::Red,
+            Self::Istanbul => Color::Black,
+            Self::Jakarta => Color::Red,
+            Self::Johannesburg => Color::Yellow,
+            Self::Karachi => Color::Black,
+            Self::Khartoum => Color::Yellow,
+            Self::Kinshasa => Color::Yellow,
+            Self::Kolkata => Color::Black,
+            Self::Lagos => Color::Yellow,
+            Self::Lima => Color::Yellow,
+            Self::London => Color::Blue,
+            Self::LosAngeles => Color::Yellow,
+            Self::Madrid => Color::Blue,
+            Self::Manila => Color::Red,
+            Self::MexicoCity => Color::Yellow,
+            Self::Miami => Color::Yellow,
+            Self::Milan => Color::Blue,
+            Self::Montreal => Color::Blue,
+            Self::Moscow => Color::Black,
+            Self::Mumbai => Color::Black,
+            Self::NewYork => Color::Blue,
+            Self::Osaka => Color::Red,
+            Self::Paris => Color::Blue,
+            Self::Riyadh => Color::Black,
+            Self::SaintPetersburg => Color::Blue,
+            Self::SanFrancisco => Color::Blue,
+            Self::Santiago => Color::Yellow,
+            Self::SaoPaulo => Color::Yellow,
+            Self::Seoul => Color::Red,
+            Self::Shanghai => Color::Red,
+            Self::Sydney => Color::Red,
+            Self::Taipei => Color::Red,
+            Self::Tehran => Color::Black,
+            Self::Tokyo => Color::Red,
+            Self::Washington => Color::Blue,
         }
     }
 }
 
 impl std::fmt::Display for Cities {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::Algiers => write!(f, "Algiers ({})", Color::Black),
-            Self::Atlanta => write!(f, "Atlanta ({})", Color::Blue),
-            Self::Baghdad => write!(f, "Baghdad ({})", Color::Black),
-            Self::Bangkok => write!(f, "Bangkok ({})", Color::Red),
-            Self::Beijing => write!(f, "Beijing ({})", Color::Red),
-            Self::Bogota => write!(f, "Bogotá ({})", Color::Yellow),
-            Self::BuenosAires => write!(f, "Buenos Aires ({})", Color::Yellow),
-            Self::Cairo => write!(f, "Cairo ({})", Color::Black),
-            Self::Chennai => write!(f, "Chennai ({})", Color::Black),
-            Self::Chicago => write!(f, "Chicago ({})", Color::Blue),
-            Self::Delhi => write!(f, "Delhi ({})", Color::Black),
-            Self::Essen => write!(f, "Essen ({})", Color::Blue),
-            Self::HoChiMinhCity => write!(f, "Ho Chi Minh City ({})", Color::Red),
-            Self::HongKong => write!(f, "Hong Kong ({})", Color::Red),
-            Self::Istanbul => write!(f, "Istanbul ({})", Color::Black),
-            Self::Jakarta => write!(f, "Jakarta ({})", Color::Red),
-            Self::Johannesburg => write!(f, "Johannesburg ({})", Color::Yellow),
-            Self::Karachi => write!(f, "Karachi ({})", Color::Black),
-            Self::Khartoum => write!(f, "Khartoum ({})", Color::Yellow),
-            Self::Kinshasa => write!(f, "Kinshasa ({})", Color::Yellow),
-            Self::Kolkata => write!(f, "Kolkata ({})", Color::Black),
-            Self::Lagos => write!(f, "Lagos ({})", Color::Yellow),
-            Self::Lima => write!(f, "Lima ({})", Color::Yellow),
-            Self::London => write!(f, "London ({})", Color::Blue),
-            Self::LosAngeles => write!(f, "Los Angeles ({})", Color::Yellow),
-            Self::Madrid => write!(f, "Madrid ({})", Color::Blue),
-            Self::Manila => write!(f, "Manila ({})", Color::Red),
-            Self::MexicoCity => write!(f, "Mexico City ({})", Color::Yellow),
-            Self::Miami => write!(f, "Miami ({})", Color::Yellow),
-            Self::Milan => write!(f, "Milan ({})", Color::Blue),
-            Self::Montreal => write!(f, "Montréal ({})", Color::Blue),
-            Self::Moscow => write!(f, "Moscow ({})", Color::Black),
-            Self::Mumbai => write!(f, "Mumbai ({})", Color::Black),
-            Self::NewYork => write!(f, "New York ({})", Color::Blue),
-            Self::Osaka => write!(f, "Osaka ({})", Color::Red),
-            Self::Paris => write!(f, "Paris ({})", Color::Blue),
-            Self::Riyadh => write!(f, "Riyadh ({})", Color::Black),
-            Self::SaintPetersburg => write!(f, "Saint Petersburg ({})", Color::Blue),
-            Self::SanFrancisco => write!(f, "San Francisco ({})", Color::Blue),
-            Self::Santiago => write!(f, "Santiago ({})", Color::Yellow),
-            Self::SaoPaulo => write!(f, "São Paulo ({})", Color::Yellow),
-            Self::Seoul => write!(f, "Seoul ({})", Color::Red),
-            Self::Shanghai => write!(f, "Shanghai ({})", Color::Red),
-            Self::Sydney => write!(f, "Sydney ({})", Color::Red),
-            Self::Taipei => write!(f, "Taipei ({})", Color::Red),
-            Self::Tehran => write!(f, "Tehran ({})", Color::Black),
-            Self::Tokyo => write!(f, "Tokyo ({})", Color::Red),
-            Self::Washington => write!(f, "Washington ({})", Color::Blue),
+        write!(f, "{} ({})", self.name(), self.color())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_city_can_outbreak_again_in_a_later_infection_step() {
+        let mut board = Board::new();
+        for _ in 0..MAX_INFECTION_PER_TYPE_PER_CITY {
+            board.infect_city(Cities::Taipei);
         }
+
+        board.infect_city(Cities::Taipei);
+        assert_eq!(board.outbreaks, 1);
+        // Within the same chain reaction, the city does not outbreak twice.
+        board.infect_city(Cities::Taipei);
+        assert_eq!(board.outbreaks, 1);
+
+        board.end_infection_step();
+        board.infect_city(Cities::Taipei);
+        assert_eq!(board.outbreaks, 2);
     }
 }
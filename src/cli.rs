@@ -0,0 +1,204 @@
+use crate::{
+    http,
+    i18n::{tr, Locale},
+    net,
+    role::Role,
+    save,
+    setup::{check_name, Difficulty, MAX_PLAYERS, MIN_PLAYERS},
+    spectate,
+    theme::Theme,
+};
+
+/// Whether this terminal plays a whole game, hosts one for others or joins one.
+#[derive(Debug, Default, PartialEq)]
+pub enum Mode {
+    #[default]
+    Local,
+    Serve(String),
+    Join(String),
+    /// Bots play over stdin and stdout in JSON lines.
+    Agent,
+    /// Shows the spectator feed of a game played elsewhere.
+    Watch(String),
+    /// Hosts games as JSON resources over HTTP.
+    Http,
+    /// Plays at this terminal on one full screen instead of scrolling menus.
+    Tui,
+}
+
+/// Setup given on the command line. Anything left out is asked for once the game starts.
+#[derive(Debug, Default)]
+pub struct Options {
+    pub(crate) players: Option<Vec<(String, Option<Role>)>>,
+    pub(crate) difficulty: Option<Difficulty>,
+    pub(crate) seed: Option<u64>,
+    pub(crate) mode: Mode,
+    /// Where to stream the game to spectators.
+    pub(crate) spectate: Option<String>,
+    /// Where a hosted game is saved after every move.
+    pub(crate) save: Option<String>,
+    /// The port the HTTP API listens on.
+    pub(crate) port: Option<u16>,
+    pub(crate) help: bool,
+}
+
+/// Reads `--players "Ana:Medic,Bo"`, one name per player with an optional role after a colon.
+pub fn parse_players(list: &str) -> Result<Vec<(String, Option<Role>)>, String> {
+    check_players(list.split(',').map(|entry| match entry.split_once(':') {
+        Some((name, role)) => (name.trim(), Some(role.trim())),
+        None => (entry.trim(), None),
+    }))
+}
+
+/// Checks the names, roles and number of players given for a game that is not set up by hand.
+pub fn check_players<'a>(
+    entries: impl IntoIterator<Item = (&'a str, Option<&'a str>)>,
+) -> Result<Vec<(String, Option<Role>)>, String> {
+    let mut players: Vec<(String, Option<Role>)> = Vec::new();
+    for (name, role) in entries {
+        let names: Vec<String> = players.iter().map(|(name, _)| name.clone()).collect();
+        check_name(name, &names)?;
+        let role = match role {
+            Some(role) => {
+                let found = Role::from_name(role)
+                    .ok_or_else(|| tr!("cli.unknown_role", role, role_names().join(", ")))?;
+                if players.iter().any(|(_, other)| *other == Some(found)) {
+                    return Err(tr!("cli.role_taken", found.name()));
+                }
+                Some(found)
+            }
+            None => None,
+        };
+        players.push((name.to_owned(), role));
+    }
+    if !(MIN_PLAYERS..=MAX_PLAYERS).contains(&players.len()) {
+        return Err(tr!("setup.player_count_error", MIN_PLAYERS, MAX_PLAYERS));
+    }
+    Ok(players)
+}
+
+fn role_names() -> Vec<&'static str> {
+    Role::ALL.iter().map(|role| role.name()).collect()
+}
+
+fn theme_names() -> Vec<&'static str> {
+    Theme::ALL.iter().map(|theme| theme.name()).collect()
+}
+
+/// Reads the flags after the program name. Both `--flag value` and `--flag=value` work.
+pub fn parse(args: &[String]) -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut args = args.iter().peekable();
+    while let Some(arg) = args.next() {
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_owned())),
+            _ => (arg.as_str(), None),
+        };
+        let mut value = || {
+            inline
+                .clone()
+                .or_else(|| args.next().cloned())
+                .ok_or_else(|| tr!("cli.missing_value", flag))
+        };
+        match flag {
+            "-h" | "--help" => options.help = true,
+            "--agent-mode" if options.mode == Mode::Local => options.mode = Mode::Agent,
+            "--players" => options.players = Some(parse_players(&value()?)?),
+            "--difficulty" => options.difficulty = Some(Difficulty::parse(&value()?)?),
+            "--spectate" => options.spectate = Some(spectate::address(&value()?)),
+            "--save" => options.save = Some(value()?),
+            "--port" => {
+                let port = value()?;
+                options.port = Some(port.parse().map_err(|_| tr!("cli.invalid_port", port))?);
+            }
+            "--seed" => {
+                let seed = value()?;
+                options.seed = Some(seed.parse().map_err(|_| tr!("cli.invalid_seed", seed))?);
+            }
+            // Already applied by `Locale::detect`.
+            "--lang" => {
+                value()?;
+            }
+            // Already applied by `Theme::detect`; checked here so a typo is not ignored.
+            "--theme" => {
+                let name = value()?;
+                if Theme::from_name(&name).is_none() {
+                    return Err(tr!("cli.unknown_theme", name, theme_names().join(", ")));
+                }
+            }
+            "serve" if options.mode == Mode::Local => {
+                let addr = match args.peek() {
+                    Some(addr) if !addr.starts_with('-') => args.next().unwrap().clone(),
+                    _ => format!("127.0.0.1:{}", net::DEFAULT_PORT),
+                };
+                options.mode = Mode::Serve(net::with_port(&addr, net::DEFAULT_PORT));
+            }
+            "join" if options.mode == Mode::Local => match args.next() {
+                Some(addr) if !addr.starts_with('-') => {
+                    options.mode = Mode::Join(net::with_port(addr, net::DEFAULT_PORT))
+                }
+                _ => return Err(tr!("cli.missing_address")),
+            },
+            "watch" if options.mode == Mode::Local => match args.next() {
+                Some(addr) if !addr.starts_with('-') => {
+                    options.mode = Mode::Watch(spectate::address(addr))
+                }
+                _ => return Err(tr!("cli.missing_address")),
+            },
+            "http" if options.mode == Mode::Local => options.mode = Mode::Http,
+            "tui" if options.mode == Mode::Local => options.mode = Mode::Tui,
+            _ => return Err(tr!("cli.unknown_flag", arg)),
+        }
+    }
+    Ok(options)
+}
+
+pub fn print_help() {
+    println!("{}", tr!("cli.usage"));
+    println!();
+    println!("{}", tr!("cli.options"));
+    let locales: Vec<&str> = Locale::ALL.iter().map(|locale| locale.code()).collect();
+    for (flag, description) in [
+        ("--players <LIST>", tr!("cli.help.players")),
+        ("--difficulty <NAME>", tr!("cli.help.difficulty")),
+        ("--seed <NUMBER>", tr!("cli.help.seed")),
+        ("--lang <CODE>", tr!("cli.help.lang", locales.join(", "))),
+        (
+            "--theme <NAME>",
+            tr!("cli.help.theme", theme_names().join(", ")),
+        ),
+        (
+            "--spectate <ADDRESS>",
+            tr!("cli.help.spectate", spectate::DEFAULT_PORT),
+        ),
+        ("--save <PATH>", tr!("cli.help.save", save::DEFAULT_PATH)),
+        ("--port <NUMBER>", tr!("cli.help.port", http::DEFAULT_PORT)),
+        ("--agent-mode", tr!("cli.help.agent")),
+        ("-h, --help", tr!("cli.help.help")),
+    ] {
+        println!("  {:<22}{}", flag, description);
+    }
+    println!();
+    println!("{}", tr!("cli.modes"));
+    for (mode, description) in [
+        ("serve [ADDRESS]", tr!("cli.help.serve", net::DEFAULT_PORT)),
+        ("join <ADDRESS>", tr!("cli.help.join")),
+        ("watch <ADDRESS>", tr!("cli.help.watch")),
+        ("http", tr!("cli.help.http")),
+        ("tui", tr!("cli.help.tui")),
+    ] {
+        println!("  {:<22}{}", mode, description);
+    }
+    println!();
+    println!("{}", tr!("cli.roles"));
+    for role in Role::ALL {
+        println!("  {}", role.name());
+    }
+    println!();
+    println!("{}", tr!("cli.difficulties"));
+    for difficulty in Difficulty::ALL {
+        println!("  {:<22}{}", difficulty.key(), difficulty);
+    }
+    println!();
+    println!("{}", tr!("cli.footer"));
+}
@@ -0,0 +1,519 @@
+use crate::{
+    board::Cities,
+    common::Color,
+    i18n::{all_texts, text, tr},
+    json::Json,
+    log::Pin,
+    player_card::Events,
+};
+
+/// A turn action typed at the prompt instead of picked from the numbered menus.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Command {
+    Pass,
+    Drive(Cities),
+    Fly(Cities),
+    Charter(Cities),
+    Shuttle(Cities),
+    Travel(Cities),
+    /// Optionally the city whose research station moves, once all of them are built.
+    Build(Option<Cities>),
+    Treat(Option<Color>),
+    Give(Cities, String),
+    Take(Cities, String),
+    Cure(Color),
+    Help(String),
+    Log,
+    Status,
+    Accept,
+    Decline,
+    Discard(Cities),
+    Play(Events),
+    /// Discarding an Event card without playing it, when over the hand limit.
+    DiscardEvent(Events),
+    /// Talk to the table.
+    Say(String),
+    /// A planning note for the city or player named first; an empty one takes it off.
+    Note(String, String),
+}
+
+impl Command {
+    /// The command as a JSON array of its kind and arguments, like `["give", "London", "Bo"]`,
+    /// with cities, colors and events by their names in the code, the same in every language.
+    pub fn to_json(&self) -> Json {
+        let name = |value: &dyn std::fmt::Debug| Json::from(format!("{:?}", value));
+        let (kind, arguments): (&str, Vec<Json>) = match self {
+            Self::Pass => ("pass", vec![]),
+            Self::Drive(to) => ("drive", vec![name(to)]),
+            Self::Fly(to) => ("fly", vec![name(to)]),
+            Self::Charter(to) => ("charter", vec![name(to)]),
+            Self::Shuttle(to) => ("shuttle", vec![name(to)]),
+            Self::Travel(to) => ("travel", vec![name(to)]),
+            Self::Build(from) => ("build", from.iter().map(|from| name(from)).collect()),
+            Self::Treat(treated) => ("treat", treated.iter().map(|color| name(color)).collect()),
+            Self::Give(card, player) => ("give", vec![name(card), player.as_str().into()]),
+            Self::Take(card, player) => ("take", vec![name(card), player.as_str().into()]),
+            Self::Cure(cured) => ("cure", vec![name(cured)]),
+            Self::Help(topic) => ("help", vec![topic.as_str().into()]),
+            Self::Log => ("log", vec![]),
+            Self::Status => ("status", vec![]),
+            Self::Accept => ("accept", vec![]),
+            Self::Decline => ("decline", vec![]),
+            Self::Discard(card) => ("discard", vec![name(card)]),
+            Self::Play(event) => ("play", vec![name(event)]),
+            Self::DiscardEvent(event) => ("discard_event", vec![name(event)]),
+            Self::Say(text) => ("say", vec![text.as_str().into()]),
+            Self::Note(target, text) => {
+                ("note", vec![target.as_str().into(), text.as_str().into()])
+            }
+        };
+        Json::array(std::iter::once(kind.into()).chain(arguments))
+    }
+
+    /// Reads back what `to_json` wrote.
+    pub fn from_json(json: &Json) -> Option<Self> {
+        let Json::Array(items) = json else {
+            return None;
+        };
+        let words: Vec<&str> = items.iter().map(Json::as_str).collect::<Option<_>>()?;
+        let city = |index: usize| named(&Cities::ALL, words.get(index)?);
+        let color = |index: usize| named(&Color::ALL, words.get(index)?);
+        let event = |index: usize| named(&Events::ALL, words.get(index)?);
+        let text = |index: usize| words.get(index).map(|word| word.to_string());
+        let command = match *words.first()? {
+            "pass" => Self::Pass,
+            "drive" => Self::Drive(city(1)?),
+            "fly" => Self::Fly(city(1)?),
+            "charter" => Self::Charter(city(1)?),
+            "shuttle" => Self::Shuttle(city(1)?),
+            "travel" => Self::Travel(city(1)?),
+            "build" if words.len() == 1 => Self::Build(None),
+            "build" => Self::Build(Some(city(1)?)),
+            "treat" if words.len() == 1 => Self::Treat(None),
+            "treat" => Self::Treat(Some(color(1)?)),
+            "give" => Self::Give(city(1)?, text(2)?),
+            "take" => Self::Take(city(1)?, text(2)?),
+            "cure" => Self::Cure(color(1)?),
+            "help" => Self::Help(text(1)?),
+            "log" => Self::Log,
+            "status" => Self::Status,
+            "accept" => Self::Accept,
+            "decline" => Self::Decline,
+            "discard" => Self::Discard(city(1)?),
+            "play" => Self::Play(event(1)?),
+            "discard_event" => Self::DiscardEvent(event(1)?),
+            "say" => Self::Say(text(1)?),
+            "note" => Self::Note(text(1)?, text(2)?),
+            _ => return None,
+        };
+        Some(command)
+    }
+}
+
+/// The value in `all` whose name in the code is `name`.
+fn named<T: Copy + std::fmt::Debug>(all: &[T], name: &str) -> Option<T> {
+    all.iter()
+        .copied()
+        .find(|value| format!("{:?}", value) == name)
+}
+
+/// The command as it would be typed, in the current language.
+impl std::fmt::Display for Command {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // The first alias of a verb is its usual spelling.
+        let word = |key: &str| text(key).split_whitespace().next().unwrap_or_default();
+        match self {
+            Self::Pass => write!(f, "{}", word("command.verb.pass")),
+            Self::Drive(to) => write!(f, "{} {}", word("command.verb.drive"), to.name()),
+            Self::Fly(to) => write!(f, "{} {}", word("command.verb.fly"), to.name()),
+            Self::Charter(to) => write!(f, "{} {}", word("command.verb.charter"), to.name()),
+            Self::Shuttle(to) => write!(f, "{} {}", word("command.verb.shuttle"), to.name()),
+            Self::Travel(to) => write!(f, "{} {}", word("command.verb.travel"), to.name()),
+            Self::Build(None) => write!(f, "{}", word("command.verb.build")),
+            Self::Build(Some(from)) => write!(
+                f,
+                "{} {} {}",
+                word("command.verb.build"),
+                word("command.word.from"),
+                from.name()
+            ),
+            Self::Treat(None) => write!(f, "{}", word("command.verb.treat")),
+            Self::Treat(Some(color)) => {
+                write!(f, "{} {}", word("command.verb.treat"), color.name())
+            }
+            Self::Give(card, player) => write!(
+                f,
+                "{} {} {} {}",
+                word("command.verb.give"),
+                card.name(),
+                word("command.word.to"),
+                player
+            ),
+            Self::Take(card, player) => write!(
+                f,
+                "{} {} {} {}",
+                word("command.verb.take"),
+                card.name(),
+                word("command.word.from"),
+                player
+            ),
+            Self::Cure(color) => write!(f, "{} {}", word("command.verb.cure"), color.name()),
+            Self::Help(topic) if topic.is_empty() => write!(f, "{}", word("command.verb.help")),
+            Self::Help(topic) => write!(f, "{} {}", word("command.verb.help"), topic),
+            Self::Log => write!(f, "{}", word("command.verb.log")),
+            Self::Status => write!(f, "{}", word("command.verb.status")),
+            Self::Accept => write!(f, "{}", word("command.verb.accept")),
+            Self::Decline => write!(f, "{}", word("command.verb.decline")),
+            Self::Discard(card) => write!(f, "{} {}", word("command.verb.discard"), card.name()),
+            Self::Play(event) => write!(f, "{} {}", word("command.verb.play"), event),
+            Self::DiscardEvent(event) => {
+                write!(f, "{} {}", word("command.verb.discard"), event)
+            }
+            Self::Say(text) => write!(f, "{} {}", word("command.verb.say"), text),
+            Self::Note(target, text) => {
+                write!(f, "{} {}: {}", word("command.verb.note"), target, text)
+            }
+        }
+    }
+}
+
+/// Lowercases, strips accents and drops everything but letters, digits and spaces.
+pub fn normalize(input: &str) -> String {
+    input
+        .to_lowercase()
+        .chars()
+        .map(|ch| match ch {
+            'á' | 'à' | 'â' | 'ã' | 'ä' => 'a',
+            'é' | 'è' | 'ê' | 'ë' => 'e',
+            'í' | 'ì' | 'î' | 'ï' => 'i',
+            'ó' | 'ò' | 'ô' | 'õ' | 'ö' => 'o',
+            'ú' | 'ù' | 'û' | 'ü' => 'u',
+            'ç' => 'c',
+            'ñ' => 'n',
+            ch => ch,
+        })
+        .filter(|ch| ch.is_alphanumeric() || ch.is_whitespace())
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + (ca != *cb) as usize;
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Picks the one candidate whose name matches `input`, trying exact, prefix, substring and
+/// finally near-miss matches. Anything left ambiguous is reported with the candidates.
+pub fn find_unique<T: Copy + PartialEq>(
+    kind: &str,
+    input: &str,
+    candidates: &[(T, String)],
+    describe: impl Fn(T) -> String,
+) -> Result<T, String> {
+    let query = normalize(input);
+    if query.is_empty() {
+        return Err(tr!("command.expected", kind));
+    }
+    let compact = query.replace(' ', "");
+    let tiers: [&dyn Fn(&str) -> bool; 4] = [
+        &|name| name == query || name.replace(' ', "") == compact,
+        &|name| name.starts_with(&query) || name.split(' ').any(|word| word.starts_with(&query)),
+        &|name| name.replace(' ', "").contains(&compact),
+        &|name| compact.len() >= 4 && edit_distance(&name.replace(' ', ""), &compact) <= 2,
+    ];
+    for tier in tiers {
+        let mut matches: Vec<T> = Vec::new();
+        for (candidate, name) in candidates {
+            if tier(name) {
+                matches.push(*candidate);
+            }
+        }
+        // The same candidate can match under several of its names.
+        matches.dedup();
+        match matches.len() {
+            0 => continue,
+            1 => return Ok(matches[0]),
+            _ => {
+                let names: Vec<String> = matches.into_iter().map(&describe).collect();
+                return Err(tr!("command.ambiguous", input.trim(), names.join(", ")));
+            }
+        }
+    }
+    Err(tr!("command.unknown_kind", kind, input.trim()))
+}
+
+pub fn find_city(input: &str) -> Result<Cities, String> {
+    let candidates: Vec<(Cities, String)> = Cities::ALL
+        .iter()
+        .flat_map(|city| {
+            all_texts(&format!("city.{:?}", city))
+                .into_iter()
+                .map(|name| (*city, normalize(name)))
+        })
+        .collect();
+    find_unique(&tr!("command.kind.city"), input, &candidates, |city| {
+        city.name().to_owned()
+    })
+}
+
+pub fn find_color(input: &str) -> Result<Color, String> {
+    let candidates: Vec<(Color, String)> = [Color::Blue, Color::Yellow, Color::Black, Color::Red]
+        .iter()
+        .flat_map(|color| {
+            all_texts(&format!("color.{:?}", color))
+                .into_iter()
+                .map(|name| (*color, normalize(name)))
+        })
+        .collect();
+    find_unique(&tr!("command.kind.color"), input, &candidates, |color| {
+        color.name().to_owned()
+    })
+}
+
+pub fn find_event(input: &str) -> Result<Events, String> {
+    let candidates: Vec<(Events, String)> = Events::ALL
+        .iter()
+        .flat_map(|event| {
+            all_texts(&format!("event.{:?}", event))
+                .into_iter()
+                .map(|name| (*event, normalize(name)))
+        })
+        .collect();
+    find_unique(&tr!("command.kind.event"), input, &candidates, |event| {
+        event.to_string()
+    })
+}
+
+/// Matches `input` against the players' names, ignoring case and accents.
+pub fn find_player(input: &str, names: &[&str]) -> Result<usize, String> {
+    let candidates: Vec<(usize, String)> = names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| (i, normalize(name)))
+        .collect();
+    find_unique(&tr!("command.kind.player"), input, &candidates, |i| {
+        names[i].to_owned()
+    })
+}
+
+/// Matches `input` against the players' names first and then the cities, for pinning a note.
+pub fn find_pin(input: &str, names: &[&str]) -> Result<Pin, String> {
+    find_player(input, names)
+        .map(|i| Pin::Player(names[i].to_owned()))
+        .or_else(|_| find_city(input).map(Pin::City))
+        .map_err(|_| tr!("command.unknown_pin", input.trim()))
+}
+
+/// Splits `rest` into a city and what follows the word under `key` in any language, e.g.
+/// "london to Alice" around "to". Names can hold the word too, as in "Ciudad de México" or a
+/// player called "Ana de Vega", so the split keeps the longest city name that matches.
+fn split_city<'a>(rest: &'a str, key: &str) -> Result<(Cities, &'a str), String> {
+    let lowercase = rest.to_lowercase();
+    let mut splits: Vec<(usize, usize)> = Vec::new();
+    for keyword in all_texts(key) {
+        let separator = format!(" {} ", keyword.to_lowercase());
+        splits.extend(
+            lowercase
+                .match_indices(&separator)
+                .map(|(idx, _)| (idx, idx + separator.len())),
+        );
+    }
+    // Lowercasing keeps the byte offsets for every letter the city and player names use.
+    splits.retain(|(start, end)| rest.is_char_boundary(*start) && rest.is_char_boundary(*end));
+    splits.sort_unstable_by_key(|(start, _)| std::cmp::Reverse(*start));
+    let Some(&(first, _)) = splits.last() else {
+        return Err(tr!("command.expected_split", tr!(key)));
+    };
+    for (start, end) in &splits {
+        if let Ok(city) = find_city(&rest[..*start]) {
+            return Ok((city, &rest[*end..]));
+        }
+    }
+    // None of the splits names a city, so report the shortest one.
+    Err(find_city(&rest[..first]).unwrap_err())
+}
+
+/// `rest` without the word under `key` in any language in front of it, e.g. "from Paris" to
+/// "Paris".
+fn after_word<'a>(rest: &'a str, key: &str) -> Result<&'a str, String> {
+    let lowercase = rest.to_ascii_lowercase();
+    for keyword in all_texts(key) {
+        let prefix = format!("{} ", keyword);
+        if lowercase.starts_with(&prefix) {
+            return Ok(rest[prefix.len()..].trim());
+        }
+    }
+    Err(tr!("command.expected_split", tr!(key)))
+}
+
+/// Whether `verb` is one of the space-separated aliases under `key` in any language.
+pub fn is_verb(verb: &str, key: &str) -> bool {
+    all_texts(key)
+        .iter()
+        .any(|aliases| aliases.split_whitespace().any(|alias| alias == verb))
+}
+
+/// `text` trimmed and kept to one line, so it can be saved like any other command.
+fn one_line(text: &str) -> String {
+    text.split(char::is_control)
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim()
+        .to_owned()
+}
+
+pub fn parse(input: &str) -> Result<Command, String> {
+    let input = input.trim();
+    let (verb, rest) = match input.split_once(char::is_whitespace) {
+        Some((verb, rest)) => (verb, rest.trim()),
+        None => (input, ""),
+    };
+    let word = normalize(verb);
+    if is_verb(&word, "command.verb.pass") {
+        Ok(Command::Pass)
+    } else if is_verb(&word, "command.verb.drive") {
+        Ok(Command::Drive(find_city(rest)?))
+    } else if is_verb(&word, "command.verb.fly") {
+        Ok(Command::Fly(find_city(rest)?))
+    } else if is_verb(&word, "command.verb.charter") {
+        Ok(Command::Charter(find_city(rest)?))
+    } else if is_verb(&word, "command.verb.shuttle") {
+        Ok(Command::Shuttle(find_city(rest)?))
+    } else if is_verb(&word, "command.verb.travel") {
+        Ok(Command::Travel(find_city(rest)?))
+    } else if is_verb(&word, "command.verb.build") {
+        if rest.is_empty() {
+            Ok(Command::Build(None))
+        } else {
+            Ok(Command::Build(Some(find_city(after_word(
+                rest,
+                "command.word.from",
+            )?)?)))
+        }
+    } else if is_verb(&word, "command.verb.treat") {
+        if rest.is_empty() {
+            Ok(Command::Treat(None))
+        } else {
+            Ok(Command::Treat(Some(find_color(rest)?)))
+        }
+    } else if is_verb(&word, "command.verb.give") {
+        let (city, player) = split_city(rest, "command.word.to")?;
+        Ok(Command::Give(city, player.trim().to_owned()))
+    } else if is_verb(&word, "command.verb.take") {
+        let (city, player) = split_city(rest, "command.word.from")?;
+        Ok(Command::Take(city, player.trim().to_owned()))
+    } else if is_verb(&word, "command.verb.cure") {
+        Ok(Command::Cure(find_color(rest)?))
+    } else if is_verb(&word, "command.verb.help") {
+        Ok(Command::Help(rest.to_owned()))
+    } else if is_verb(&word, "command.verb.log") {
+        Ok(Command::Log)
+    } else if is_verb(&word, "command.verb.status") {
+        Ok(Command::Status)
+    } else if is_verb(&word, "command.verb.accept") {
+        Ok(Command::Accept)
+    } else if is_verb(&word, "command.verb.decline") {
+        Ok(Command::Decline)
+    } else if is_verb(&word, "command.verb.discard") {
+        // City cards are tried first, so an unknown name is reported as a city.
+        find_city(rest).map(Command::Discard).or_else(|error| {
+            find_event(rest)
+                .map(Command::DiscardEvent)
+                .map_err(|_| error)
+        })
+    } else if is_verb(&word, "command.verb.play") {
+        Ok(Command::Play(find_event(rest)?))
+    } else if is_verb(&word, "command.verb.say") {
+        match one_line(rest) {
+            text if text.is_empty() => Err(tr!("command.expected_text")),
+            text => Ok(Command::Say(text)),
+        }
+    } else if is_verb(&word, "command.verb.note") {
+        let (target, text) = rest
+            .split_once(':')
+            .ok_or_else(|| tr!("command.expected_note"))?;
+        Ok(Command::Note(target.trim().to_owned(), one_line(text)))
+    } else {
+        Err(tr!("command.unknown", verb, tr!("command.usage")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gives_a_card_to_a_player() {
+        assert_eq!(
+            parse("give london to Alice"),
+            Ok(Command::Give(Cities::London, "Alice".to_owned()))
+        );
+    }
+
+    #[test]
+    fn player_names_may_hold_the_separator() {
+        assert_eq!(
+            parse("give london to Tom to Jerry"),
+            Ok(Command::Give(Cities::London, "Tom to Jerry".to_owned()))
+        );
+        assert_eq!(
+            parse("take mexico city from Ana de Vega"),
+            Ok(Command::Take(Cities::MexicoCity, "Ana de Vega".to_owned()))
+        );
+    }
+
+    #[test]
+    fn city_names_may_hold_the_separator() {
+        assert_eq!(
+            parse("tomar Ciudad de México de Ana"),
+            Ok(Command::Take(Cities::MexicoCity, "Ana".to_owned()))
+        );
+    }
+
+    #[test]
+    fn a_split_without_a_city_is_an_error() {
+        assert!(parse("give nowhere to Alice").is_err());
+        assert!(parse("give london Alice").is_err());
+    }
+
+    #[test]
+    fn commands_read_back_from_json() {
+        let commands = [
+            Command::Pass,
+            Command::Drive(Cities::SaoPaulo),
+            Command::Build(None),
+            Command::Build(Some(Cities::Atlanta)),
+            Command::Treat(None),
+            Command::Treat(Some(Color::Black)),
+            Command::Give(Cities::London, "Bo Li".to_owned()),
+            Command::Cure(Color::Red),
+            Command::DiscardEvent(Events::Airlift),
+            Command::Say("\"over here\", dijo José".to_owned()),
+            Command::Note("Lima".to_owned(), String::new()),
+        ];
+        for command in commands {
+            let line = command.to_json().to_string();
+            assert!(!line.contains('\n'));
+            assert_eq!(
+                crate::json::parse(&line)
+                    .ok()
+                    .and_then(|json| Command::from_json(&json)),
+                Some(command)
+            );
+        }
+        let atlantis = Json::array(["drive", "Atlantis"]);
+        assert_eq!(Command::from_json(&atlantis), None);
+        assert_eq!(Command::from_json(&"drive Atlanta".into()), None);
+    }
+}
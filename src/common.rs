@@ -1,5 +1,10 @@
 use crossterm::style::{Color as ConsoleColor, Stylize};
 
+use crate::{
+    i18n::text,
+    theme::{Theme, Themed},
+};
+
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
 pub enum Color {
     Blue,
@@ -8,13 +13,37 @@ pub enum Color {
     Red,
 }
 
-impl std::fmt::Display for Color {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl Color {
+    pub const ALL: [Color; 4] = [Color::Blue, Color::Yellow, Color::Black, Color::Red];
+
+    pub fn console_color(&self) -> ConsoleColor {
+        Theme::current().disease_color(*self)
+    }
+
+    pub fn name(&self) -> &'static str {
+        text(&format!("color.{:?}", self))
+    }
+
+    /// Single letter for places too small for the name; Black is K so it differs from Blue.
+    pub fn letter(&self) -> char {
         match self {
-            Self::Yellow => write!(f, "{}", "Yellow".with(ConsoleColor::Yellow)),
-            Self::Black => write!(f, "{}", "Black".with(ConsoleColor::DarkMagenta)),
-            Self::Blue => write!(f, "{}", "Blue".with(ConsoleColor::Blue)),
-            Self::Red => write!(f, "{}", "Red".with(ConsoleColor::Red)),
+            Self::Yellow => 'Y',
+            Self::Black => 'K',
+            Self::Blue => 'B',
+            Self::Red => 'R',
         }
     }
 }
+
+impl std::fmt::Display for Color {
+    /// The name in its color, with the letter from the map after it in the themes that need
+    /// one, so hands and menus can be read without the colors.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = if Theme::current().uses_letters() {
+            format!("{} [{}]", self.name(), self.letter())
+        } else {
+            self.name().to_owned()
+        };
+        write!(f, "{}", name.with(self.console_color()).themed())
+    }
+}
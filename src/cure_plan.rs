@@ -0,0 +1,389 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::{
+    board::{Board, Cities, DiseaseState},
+    common::Color,
+    i18n::tr,
+    player::{self, Player},
+    role::Role,
+};
+
+/// One Share Knowledge needed to get a card into the curing player's hand.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Transfer {
+    from: usize,
+    card: Cities,
+    meet: Cities,
+}
+
+/// The fewest-turn way found for one player to gather the cards and cure a color.
+#[derive(Clone, Debug)]
+struct CurePlan {
+    curer: usize,
+    transfers: Vec<Transfer>,
+    station: Cities,
+    /// The actions each player spends on the plan, in team order.
+    actions: Vec<usize>,
+}
+
+impl CurePlan {
+    /// Turns are taken side by side, so the plan takes as long as its busiest player.
+    fn turns(&self) -> usize {
+        self.actions
+            .iter()
+            .map(|actions| actions.div_ceil(player::ACTIONS_PER_TURN as usize))
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn total(&self) -> usize {
+        self.actions.iter().sum()
+    }
+
+    fn cost(&self) -> (usize, usize) {
+        (self.turns(), self.total())
+    }
+}
+
+/// Actions needed to walk between any two cities by Drive / Ferry and Shuttle Flight, without
+/// spending any City cards.
+fn distances(board: &Board) -> HashMap<(Cities, Cities), usize> {
+    let stations = board.research_stations();
+    let mut table = HashMap::new();
+    for from in board.all_cities() {
+        let mut queue = VecDeque::from([(from, 0)]);
+        table.insert((from, from), 0);
+        while let Some((city, distance)) = queue.pop_front() {
+            let mut next: Vec<Cities> = board
+                .adjacent_to(city)
+                .unwrap_or_default()
+                .into_iter()
+                .collect();
+            if stations.contains(&city) {
+                next.extend(&stations);
+            }
+            for to in next {
+                if let std::collections::hash_map::Entry::Vacant(entry) = table.entry((from, to)) {
+                    entry.insert(distance + 1);
+                    queue.push_back((to, distance + 1));
+                }
+            }
+        }
+    }
+    table
+}
+
+/// A card a teammate could hand to the curer.
+#[derive(Clone, Copy)]
+struct Offer {
+    from: usize,
+    card: Cities,
+}
+
+/// Tries every order of Share Knowledge for one curer, moving both players to each meeting,
+/// and keeps the plan with the fewest turns, then the fewest actions.
+struct Search<'a> {
+    distances: &'a HashMap<(Cities, Cities), usize>,
+    stations: &'a [Cities],
+    researchers: Vec<bool>,
+    curer: usize,
+    offers: Vec<Offer>,
+    best: Option<CurePlan>,
+}
+
+impl Search<'_> {
+    fn distance(&self, from: Cities, to: Cities) -> usize {
+        self.distances
+            .get(&(from, to))
+            .copied()
+            .unwrap_or(usize::MAX / 4)
+    }
+
+    fn run(
+        &mut self,
+        needed: usize,
+        used: &mut Vec<bool>,
+        locations: &mut Vec<Cities>,
+        plan: &mut CurePlan,
+    ) {
+        if self
+            .best
+            .as_ref()
+            .is_some_and(|best| plan.cost() >= best.cost())
+        {
+            return;
+        }
+        if needed == 0 {
+            let from = locations[self.curer];
+            let Some(&station) = self
+                .stations
+                .iter()
+                .min_by_key(|station| self.distance(from, **station))
+            else {
+                return;
+            };
+            let mut done = plan.clone();
+            done.station = station;
+            done.actions[self.curer] += self.distance(from, station) + 1;
+            if self
+                .best
+                .as_ref()
+                .is_none_or(|best| done.cost() < best.cost())
+            {
+                self.best = Some(done);
+            }
+            return;
+        }
+        for idx in 0..self.offers.len() {
+            if used[idx] {
+                continue;
+            }
+            let Offer { from, card } = self.offers[idx];
+            // The Researcher may hand over any City card wherever they meet, so either of the
+            // two can walk to the other. Anyone else has to be in the card's city.
+            let meets = if self.researchers[from] {
+                vec![locations[from], locations[self.curer]]
+            } else {
+                vec![card]
+            };
+            for meet in meets {
+                let (giver_was, curer_was) = (locations[from], locations[self.curer]);
+                let giver_walk = self.distance(giver_was, meet);
+                let curer_walk = self.distance(curer_was, meet) + 1;
+                used[idx] = true;
+                locations[from] = meet;
+                locations[self.curer] = meet;
+                plan.actions[from] += giver_walk;
+                plan.actions[self.curer] += curer_walk;
+                plan.transfers.push(Transfer { from, card, meet });
+
+                self.run(needed - 1, used, locations, plan);
+
+                plan.transfers.pop();
+                plan.actions[self.curer] -= curer_walk;
+                plan.actions[from] -= giver_walk;
+                locations[self.curer] = curer_was;
+                locations[from] = giver_was;
+                used[idx] = false;
+            }
+        }
+    }
+}
+
+fn plan_for(
+    board: &Board,
+    distances: &HashMap<(Cities, Cities), usize>,
+    team: &[&Player],
+    curer: usize,
+    color: Color,
+) -> Option<CurePlan> {
+    let needed = team[curer]
+        .cards_to_cure()
+        .saturating_sub(team[curer].cards_of_color(color).len());
+    let offers: Vec<Offer> = team
+        .iter()
+        .enumerate()
+        .filter(|(from, _)| *from != curer)
+        .flat_map(|(from, player)| {
+            player
+                .cards_of_color(color)
+                .into_iter()
+                .map(move |card| Offer { from, card })
+        })
+        .collect();
+    if offers.len() < needed {
+        return None;
+    }
+
+    let stations = board.research_stations();
+    let mut search = Search {
+        distances,
+        stations: &stations,
+        researchers: team
+            .iter()
+            .map(|player| player.role() == Role::Researcher)
+            .collect(),
+        curer,
+        offers,
+        best: None,
+    };
+    let mut plan = CurePlan {
+        curer,
+        transfers: Vec::new(),
+        station: team[curer].location(),
+        actions: vec![0; team.len()],
+    };
+    search.run(
+        needed,
+        &mut vec![false; search.offers.len()],
+        &mut team.iter().map(|player| player.location()).collect(),
+        &mut plan,
+    );
+    search.best
+}
+
+/// The fewest-turn plan for any player to cure `color`.
+fn best_plan(board: &Board, team: &[&Player], color: Color) -> Option<CurePlan> {
+    let distances = distances(board);
+    (0..team.len())
+        .filter_map(|curer| plan_for(board, &distances, team, curer, color))
+        .min_by_key(CurePlan::cost)
+}
+
+/// Shows, for each uncured color, who holds the cards, where they can be shared and the
+/// quickest plan found to bring them together at a research station.
+pub fn show_cure_plan(board: &Board, player: &Player, others: &[Player]) {
+    let mut team = vec![player];
+    team.extend(others.iter());
+
+    println!("{:=^94}", format!(" {} ", tr!("cure_plan.title")));
+    for color in [Color::Blue, Color::Yellow, Color::Black, Color::Red] {
+        if board.disease_state(color) != DiseaseState::Default {
+            println!("{}: {}", color, board.disease_state(color));
+            continue;
+        }
+        println!("{}:", color);
+        for member in &team {
+            let held = member.cards_of_color(color);
+            println!(
+                "\t{}",
+                tr!(
+                    "cure_plan.holds",
+                    member.name(),
+                    held.len(),
+                    member.cards_to_cure(),
+                    color,
+                    member.cards_to_cure().saturating_sub(held.len())
+                )
+            );
+            for card in held {
+                if member.role() == Role::Researcher {
+                    println!(
+                        "\t\t{}",
+                        tr!(
+                            "cure_plan.meet_anywhere",
+                            card,
+                            member.name(),
+                            member.location()
+                        )
+                    );
+                } else {
+                    println!(
+                        "\t\t{}",
+                        tr!("cure_plan.meet_in", card, member.name(), card)
+                    );
+                }
+            }
+        }
+
+        match best_plan(board, &team, color) {
+            Some(plan) => {
+                let turns = plan.turns();
+                let key = if turns == 1 {
+                    "cure_plan.plan.one"
+                } else {
+                    "cure_plan.plan.other"
+                };
+                println!(
+                    "\t{}",
+                    tr!(key, plan.total(), turns, team[plan.curer].name())
+                );
+                for (member, actions) in team.iter().zip(&plan.actions) {
+                    if *actions > 0 {
+                        println!("\t\t{}", tr!("cure_plan.spends", member.name(), actions));
+                    }
+                }
+                for transfer in &plan.transfers {
+                    println!(
+                        "\t\t{}",
+                        tr!(
+                            "cure_plan.take",
+                            team[plan.curer].name(),
+                            transfer.card,
+                            team[transfer.from].name(),
+                            transfer.meet
+                        )
+                    );
+                }
+                println!(
+                    "\t\t{}",
+                    tr!("cure_plan.cure_at", team[plan.curer].name(), plan.station)
+                );
+            }
+            None => println!("\t{}", tr!("cure_plan.not_enough", color)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn player(name: &str, role: Role, city: Cities, cards: &[Cities]) -> Player {
+        Player::new(name, role).at(city).holding(cards)
+    }
+
+    fn medic_short_one_card() -> Player {
+        player(
+            "Ana",
+            Role::Medic,
+            Cities::Atlanta,
+            &[
+                Cities::Chicago,
+                Cities::Montreal,
+                Cities::London,
+                Cities::Madrid,
+            ],
+        )
+    }
+
+    #[test]
+    fn counts_the_giving_players_travel() {
+        let board = Board::new();
+        let ana = medic_short_one_card();
+        let bo = player("Bo", Role::Dispatcher, Cities::Tokyo, &[Cities::Atlanta]);
+        let cy = player(
+            "Cy",
+            Role::QuarantineSpecialist,
+            Cities::Washington,
+            &[Cities::Washington],
+        );
+
+        let plan = best_plan(&board, &[&ana, &bo, &cy], Color::Blue).unwrap();
+        // Bo's card is for the city Ana stands in, but Bo is three moves away from it.
+        assert_eq!(plan.curer, 0);
+        assert_eq!(
+            plan.transfers,
+            vec![Transfer {
+                from: 2,
+                card: Cities::Washington,
+                meet: Cities::Washington,
+            }]
+        );
+        assert_eq!(plan.station, Cities::Atlanta);
+        assert_eq!(plan.actions, vec![4, 0, 0]);
+        assert_eq!(plan.turns(), 1);
+    }
+
+    #[test]
+    fn the_researcher_comes_to_the_curer_when_that_is_quicker() {
+        let board = Board::new();
+        let ana = medic_short_one_card();
+        let rex = player("Rex", Role::Researcher, Cities::Tokyo, &[Cities::Paris]);
+
+        let plan = best_plan(&board, &[&ana, &rex], Color::Blue).unwrap();
+        assert_eq!(plan.curer, 0);
+        assert_eq!(
+            plan.transfers,
+            vec![Transfer {
+                from: 1,
+                card: Cities::Paris,
+                meet: Cities::Atlanta,
+            }]
+        );
+        // Rex's three moves are counted too, but they fit in Rex's own turn.
+        assert_eq!(plan.actions, vec![2, 3]);
+        assert_eq!(plan.turns(), 1);
+        assert_eq!(plan.total(), 5);
+    }
+}
@@ -1,5 +1,10 @@
 use rand::Rng;
-use std::collections::{vec_deque::IntoIter, VecDeque};
+use std::collections::{
+    vec_deque::{IntoIter, Iter},
+    VecDeque,
+};
+
+use crate::random::with_rng;
 
 #[derive(Debug)]
 pub struct Deck<T>(pub(crate) VecDeque<T>);
@@ -13,10 +18,6 @@ impl<T> Deck<T> {
         self.0.push_back(elem);
     }
 
-    pub fn discard_to_bottom(&mut self, elem: T) {
-        self.0.push_front(elem);
-    }
-
     pub fn draw_from_top(&mut self) -> Option<T> {
         self.0.pop_back()
     }
@@ -26,20 +27,33 @@ impl<T> Deck<T> {
     }
 
     pub fn shuffle(&mut self) {
-        let mut rng = rand::thread_rng();
-
-        let mut i = self.0.len();
-        while i >= 2 {
-            // invariant: elements with index >= i have been locked in place.
-            i -= 1;
-            // lock element i in place.
-            self.0.swap(i, rng.gen_range(0..i + 1));
-        }
+        with_rng(|rng| {
+            let mut i = self.0.len();
+            while i >= 2 {
+                // invariant: elements with index >= i have been locked in place.
+                i -= 1;
+                // lock element i in place.
+                self.0.swap(i, rng.gen_range(0..i + 1));
+            }
+        });
     }
 
     pub fn append(&mut self, other: &mut Self) {
         self.0.append(&mut other.0)
     }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Iterates from the bottom of the deck to the top.
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.0.iter()
+    }
 }
 
 impl<T> IntoIterator for Deck<T> {
@@ -0,0 +1,391 @@
+use crate::{
+    board::{Cities, DiseaseState},
+    command::Command,
+    common::Color,
+    game::{self, Game, Outcome, Phase, Seeded},
+    i18n::tr,
+    player_card::{Events, PlayerCard},
+    role::Role,
+    setup::{self, Difficulty, MAX_PLAYERS, MIN_PLAYERS},
+};
+
+const CITIES: usize = Cities::ALL.len();
+const COLORS: usize = Color::ALL.len();
+const EVENTS: usize = Events::ALL.len();
+const ROLES: usize = Role::ALL.len();
+/// The other players a card can be shared with, counted from the one acting.
+const OTHERS: usize = MAX_PLAYERS - 1;
+
+// Where each kind of action starts in the action space. Cities and colors go in the order of
+// `Cities::ALL` and `Color::ALL`; cards are shared with the 1st, 2nd or 3rd player after the one
+// acting.
+const DRIVE: usize = 1;
+const FLY: usize = DRIVE + CITIES;
+const CHARTER: usize = FLY + CITIES;
+const SHUTTLE: usize = CHARTER + CITIES;
+const BUILD: usize = SHUTTLE + CITIES;
+/// Building while moving the station from each city, once all of them are built.
+const BUILD_FROM: usize = BUILD + 1;
+const TREAT: usize = BUILD_FROM + CITIES;
+const GIVE: usize = TREAT + COLORS;
+const TAKE: usize = GIVE + CITIES * OTHERS;
+const CURE: usize = TAKE + CITIES * OTHERS;
+const ACCEPT: usize = CURE + COLORS;
+const DECLINE: usize = ACCEPT + 1;
+const DISCARD: usize = DECLINE + 1;
+/// Playing each Event card. Kept for when the cards do what they say; never legal until then.
+const PLAY: usize = DISCARD + CITIES;
+const DISCARD_EVENT: usize = PLAY + EVENTS;
+
+/// How many actions there are, whether or not they are legal right now: pass, then drive, fly,
+/// charter and shuttle to each city, build, treat, give, take, cure, accept, decline, discard
+/// each City card, play each Event card (never legal for now) and discard each Event card.
+pub const ACTIONS: usize = DISCARD_EVENT + EVENTS;
+
+/// How many numbers an observation has. In order:
+///
+/// - the cubes of each color on each city, city by city,
+/// - whether each city has a research station,
+/// - each disease: 0 while spreading, 1 once cured and 2 once eradicated,
+/// - for each seat, starting from the player acting: a one-hot of their city, a one-hot of
+///   their role, and their hand as one slot per city and Event card (all zero for empty seats),
+/// - whether each city is in the infection discard pile,
+/// - outbreaks, infection rate, epidemics so far, cards left in the Player Deck and actions
+///   left this turn,
+/// - a one-hot of what the game waits for: actions, an answer to an offer or a discard,
+/// - during an offer: a one-hot of the card, a one-hot of who offers it, counted like the
+///   receivers of shared cards, and whether they give it (1) or take it (0); all zero
+///   otherwise.
+pub const OBSERVATION_SIZE: usize = CITIES * COLORS
+    + CITIES
+    + COLORS
+    + MAX_PLAYERS * (CITIES + ROLES + CITIES + EVENTS)
+    + CITIES
+    + 5
+    + 3
+    + CITIES
+    + OTHERS
+    + 1;
+
+fn city_index(city: Cities) -> usize {
+    Cities::ALL.iter().position(|other| *other == city).unwrap()
+}
+
+fn color_index(color: Color) -> usize {
+    Color::ALL.iter().position(|other| *other == color).unwrap()
+}
+
+/// The seats in the order the observation and the actions count them: the player acting
+/// first, then the others in turn order.
+fn seats(game: &Game) -> impl Iterator<Item = usize> + '_ {
+    let first = game.waiting_on().unwrap_or(game.current);
+    let count = game.players.len();
+    (0..count).map(move |offset| (first + offset) % count)
+}
+
+/// The command numbered `index`, for the player `game` is waiting on.
+pub(crate) fn command(game: &Game, index: usize) -> Option<Command> {
+    let seats: Vec<usize> = seats(game).collect();
+    let shared = |first: usize| {
+        let i = index - first;
+        let other = seats.get(i / CITIES + 1)?;
+        Some((
+            Cities::ALL[i % CITIES],
+            game.players[*other].plain_name().to_owned(),
+        ))
+    };
+    Some(match index {
+        0 => Command::Pass,
+        i if i < FLY => Command::Drive(Cities::ALL[i - DRIVE]),
+        i if i < CHARTER => Command::Fly(Cities::ALL[i - FLY]),
+        i if i < SHUTTLE => Command::Charter(Cities::ALL[i - CHARTER]),
+        i if i < BUILD => Command::Shuttle(Cities::ALL[i - SHUTTLE]),
+        BUILD => Command::Build(None),
+        i if i < TREAT => Command::Build(Some(Cities::ALL[i - BUILD_FROM])),
+        i if i < GIVE => Command::Treat(Some(Color::ALL[i - TREAT])),
+        i if i < TAKE => {
+            let (card, name) = shared(GIVE)?;
+            Command::Give(card, name)
+        }
+        i if i < CURE => {
+            let (card, name) = shared(TAKE)?;
+            Command::Take(card, name)
+        }
+        i if i < ACCEPT => Command::Cure(Color::ALL[i - CURE]),
+        ACCEPT => Command::Accept,
+        DECLINE => Command::Decline,
+        i if i < PLAY => Command::Discard(Cities::ALL[i - DISCARD]),
+        i if i < DISCARD_EVENT => return None,
+        i if i < ACTIONS => Command::DiscardEvent(Events::ALL[i - DISCARD_EVENT]),
+        _ => return None,
+    })
+}
+
+/// The number of `command`, given by the player `game` is waiting on.
+pub(crate) fn index(game: &Game, command: &Command) -> Option<usize> {
+    let other = |name: &str| {
+        seats(game)
+            .skip(1)
+            .position(|seat| game.players[seat].plain_name() == name)
+    };
+    Some(match command {
+        Command::Pass => 0,
+        Command::Drive(city) => DRIVE + city_index(*city),
+        Command::Fly(city) => FLY + city_index(*city),
+        Command::Charter(city) => CHARTER + city_index(*city),
+        Command::Shuttle(city) => SHUTTLE + city_index(*city),
+        Command::Build(None) => BUILD,
+        Command::Build(Some(city)) => BUILD_FROM + city_index(*city),
+        Command::Treat(Some(color)) => TREAT + color_index(*color),
+        Command::Give(card, name) => GIVE + other(name)? * CITIES + city_index(*card),
+        Command::Take(card, name) => TAKE + other(name)? * CITIES + city_index(*card),
+        Command::Cure(color) => CURE + color_index(*color),
+        Command::Accept => ACCEPT,
+        Command::Decline => DECLINE,
+        Command::Discard(city) => DISCARD + city_index(*city),
+        Command::DiscardEvent(event) => {
+            DISCARD_EVENT + Events::ALL.iter().position(|e| e == event)?
+        }
+        _ => return None,
+    })
+}
+
+/// Which actions are legal now, by their numbers.
+pub(crate) fn action_mask(game: &Game) -> Vec<bool> {
+    let mut mask = vec![false; ACTIONS];
+    for command in game.legal_actions() {
+        if let Some(index) = index(game, &command) {
+            mask[index] = true;
+        }
+    }
+    mask
+}
+
+/// What a step did: the observation after it, the reward and whether the game is over.
+#[derive(Debug)]
+pub struct Step {
+    pub observation: Vec<f32>,
+    /// 1 for winning, -1 for losing and 0 while the game goes on.
+    pub reward: f32,
+    pub done: bool,
+}
+
+/// Pandemic as a reinforcement learning environment: a fixed set of numbered actions, a
+/// fixed-size observation and a mask of which actions are legal. The agent plays every seat,
+/// always acting for the player the game is waiting on.
+pub struct Env {
+    players: usize,
+    difficulty: Difficulty,
+    seeded: Seeded,
+}
+
+impl Env {
+    pub fn new(players: usize, difficulty: Difficulty) -> Result<Self, String> {
+        if !(MIN_PLAYERS..=MAX_PLAYERS).contains(&players) {
+            return Err(tr!("setup.player_count_error", MIN_PLAYERS, MAX_PLAYERS));
+        }
+        game::set_quiet(true);
+        Ok(Self {
+            players,
+            difficulty,
+            seeded: Self::deal(players, difficulty, 0),
+        })
+    }
+
+    fn deal(players: usize, difficulty: Difficulty, seed: u64) -> Seeded {
+        let named: Vec<(String, Option<Role>)> = (1..=players)
+            .map(|number| (tr!("env.player", number), None))
+            .collect();
+        Seeded::deal(&setup::deal_roles(&named, difficulty), seed)
+    }
+
+    /// Deals a new game from `seed` and returns the first observation.
+    pub fn reset(&mut self, seed: u64) -> Vec<f32> {
+        self.seeded = Self::deal(self.players, self.difficulty, seed);
+        self.observation()
+    }
+
+    /// Takes action `index` for the player the game is waiting on.
+    pub fn step(&mut self, index: usize) -> Result<Step, String> {
+        let Some(seat) = self.seeded.game.waiting_on() else {
+            return Err(tr!("env.over"));
+        };
+        let command = self
+            .command(index)
+            .filter(|command| self.seeded.game.legal_actions().contains(command))
+            .ok_or_else(|| tr!("env.illegal", index))?;
+        self.seeded.apply(seat, &command)?;
+        let (reward, done) = match self.seeded.game.phase {
+            Phase::Over(Outcome::Victory) => (1.0, true),
+            Phase::Over(_) => (-1.0, true),
+            _ => (0.0, false),
+        };
+        Ok(Step {
+            observation: self.observation(),
+            reward,
+            done,
+        })
+    }
+
+    pub fn game(&self) -> &Game {
+        &self.seeded.game
+    }
+
+    /// The command numbered `index`, for the player the game is waiting on.
+    pub fn command(&self, index: usize) -> Option<Command> {
+        command(&self.seeded.game, index)
+    }
+
+    /// The number of `command`, given by the player the game is waiting on.
+    pub fn index(&self, command: &Command) -> Option<usize> {
+        self::index(&self.seeded.game, command)
+    }
+
+    /// Which actions are legal now, by their numbers.
+    pub fn action_mask(&self) -> Vec<bool> {
+        action_mask(&self.seeded.game)
+    }
+
+    /// The table as numbers, laid out as `OBSERVATION_SIZE` describes.
+    pub fn observation(&self) -> Vec<f32> {
+        let board = &self.seeded.game.board;
+        let mut observation = Vec::with_capacity(OBSERVATION_SIZE);
+        for city in Cities::ALL {
+            for color in Color::ALL {
+                observation.push(board.map[&city].infection_count(color) as f32);
+            }
+        }
+        for city in Cities::ALL {
+            observation.push(board.map[&city].has_research_station as u8 as f32);
+        }
+        for color in Color::ALL {
+            observation.push(match board.disease_state(color) {
+                DiseaseState::Default => 0.0,
+                DiseaseState::Cured => 1.0,
+                DiseaseState::Eradicated => 2.0,
+            });
+        }
+        let seats: Vec<usize> = seats(&self.seeded.game).collect();
+        for slot in 0..MAX_PLAYERS {
+            let mut location = [0.0; CITIES];
+            let mut role = [0.0; ROLES];
+            let mut hand = [0.0; CITIES + EVENTS];
+            if let Some(player) = seats.get(slot).map(|seat| &self.seeded.game.players[*seat]) {
+                location[city_index(player.location())] = 1.0;
+                role[Role::ALL.iter().position(|r| *r == player.role()).unwrap()] = 1.0;
+                for card in &player.hand {
+                    match card {
+                        PlayerCard::CityCard(held) => hand[city_index(held.city)] = 1.0,
+                        PlayerCard::EventCard(held) => {
+                            let event = Events::ALL.iter().position(|e| *e == held.event());
+                            hand[CITIES + event.unwrap()] = 1.0;
+                        }
+                        PlayerCard::EpidemicCard => {}
+                    }
+                }
+            }
+            observation.extend(location);
+            observation.extend(role);
+            observation.extend(hand);
+        }
+        let mut discarded = [0.0; CITIES];
+        for card in board.infection_discard.iter() {
+            discarded[city_index(card.city)] = 1.0;
+        }
+        observation.extend(discarded);
+        observation.extend([
+            board.outbreaks as f32,
+            board.infection_rate() as f32,
+            board.epidemics as f32,
+            board.player_deck.len() as f32,
+            self.seeded.game.actions_left as f32,
+        ]);
+        observation.extend(match self.seeded.game.phase {
+            Phase::Actions => [1.0, 0.0, 0.0],
+            Phase::Offer { .. } => [0.0, 1.0, 0.0],
+            Phase::Discard { .. } => [0.0, 0.0, 1.0],
+            Phase::Over(_) => [0.0, 0.0, 0.0],
+        });
+        let mut offered = [0.0; CITIES];
+        let mut offering = [0.0; OTHERS];
+        let mut giving = 0.0;
+        if let Phase::Offer {
+            card,
+            giving: gives,
+            ..
+        } = self.seeded.game.phase
+        {
+            offered[city_index(card)] = 1.0;
+            let from = seats
+                .iter()
+                .position(|seat| *seat == self.seeded.game.current);
+            offering[from.unwrap() - 1] = 1.0;
+            giving = gives as u8 as f32;
+        }
+        observation.extend(offered);
+        observation.extend(offering);
+        observation.push(giving);
+        observation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Plays `env` on with the legal action `turn` picks, checking every step on the way.
+    fn check_every_step(env: &mut Env, seed: u64) {
+        env.reset(seed);
+        for turn in 0..400 {
+            assert_eq!(env.observation().len(), OBSERVATION_SIZE);
+            let mask = env.action_mask();
+            assert_eq!(mask.len(), ACTIONS);
+            let legal: Vec<usize> = (0..ACTIONS).filter(|i| mask[*i]).collect();
+            for &i in &legal {
+                let command = env.command(i).unwrap();
+                assert_eq!(env.index(&command), Some(i), "{:?}", command);
+            }
+            for command in env.game().legal_actions() {
+                if !matches!(command, Command::Say(_) | Command::Note(..)) {
+                    let i = env.index(&command);
+                    assert!(i.is_some_and(|i| mask[i]), "{:?} has no bit", command);
+                }
+            }
+            if legal.is_empty() {
+                break;
+            }
+            if env.step(legal[turn * 13 % legal.len()]).unwrap().done {
+                break;
+            }
+        }
+    }
+
+    #[test]
+    fn actions_mask_and_observation_agree_throughout_a_game() {
+        for (players, seed) in [(2, 1), (3, 2), (4, 3)] {
+            let mut env = Env::new(players, Difficulty::Introductory).unwrap();
+            check_every_step(&mut env, seed);
+        }
+    }
+
+    #[test]
+    fn an_offer_shows_the_card_and_who_offers_it() {
+        let mut env = Env::new(2, Difficulty::Introductory).unwrap();
+        env.reset(5);
+        let game = &mut env.seeded.game;
+        let other = 1 - game.current;
+        game.phase = Phase::Offer {
+            to: other,
+            card: Cities::Paris,
+            giving: true,
+        };
+        let observation = env.observation();
+        let offer = &observation[OBSERVATION_SIZE - CITIES - OTHERS - 1..];
+        assert_eq!(offer[city_index(Cities::Paris)], 1.0);
+        assert_eq!(offer[..CITIES].iter().sum::<f32>(), 1.0);
+        // The offer comes from the first player after the one answering.
+        assert_eq!(offer[CITIES], 1.0);
+        assert_eq!(offer[CITIES + 1..CITIES + OTHERS].iter().sum::<f32>(), 0.0);
+        assert_eq!(offer[CITIES + OTHERS], 1.0);
+    }
+}
@@ -0,0 +1,114 @@
+/// One of the piles the Player Deck was built from in `Deck::<PlayerCard>::add_epidemic_cards`.
+#[derive(Debug, Clone, Copy)]
+pub struct Pile {
+    pub(crate) size: usize,
+    pub(crate) has_epidemic: bool,
+}
+
+/// The pile boundaries of the Player Deck, bottom first like `Deck`.
+///
+/// Each pile hides at most one Epidemic card, so the number of cards drawn and the number of
+/// Epidemics seen so far pin down which pile is on top and whether its Epidemic is still in it.
+#[derive(Debug, Default)]
+pub struct EpidemicTiming {
+    piles: Vec<Pile>,
+}
+
+impl EpidemicTiming {
+    pub fn new(piles: Vec<Pile>) -> Self {
+        Self { piles }
+    }
+
+    pub fn total_cards(&self) -> usize {
+        self.piles.iter().map(|pile| pile.size).sum()
+    }
+
+    /// Chance of drawing at least one Epidemic within the next `draws` cards.
+    pub fn epidemic_chance(&self, cards_left: usize, epidemics_drawn: u8, draws: usize) -> f64 {
+        let mut already_drawn = self.total_cards().saturating_sub(cards_left);
+        let mut epidemics_drawn = epidemics_drawn as usize;
+        let mut draws = draws;
+        let mut no_epidemic = 1.0;
+        for pile in self.piles.iter().rev() {
+            if draws == 0 {
+                break;
+            }
+            if already_drawn >= pile.size {
+                already_drawn -= pile.size;
+                if pile.has_epidemic {
+                    epidemics_drawn = epidemics_drawn.saturating_sub(1);
+                }
+                continue;
+            }
+            let remaining = pile.size - already_drawn;
+            let taken = draws.min(remaining);
+            if pile.has_epidemic && epidemics_drawn == 0 {
+                no_epidemic *= (remaining - taken) as f64 / remaining as f64;
+            }
+            already_drawn = 0;
+            epidemics_drawn = 0;
+            draws -= taken;
+        }
+        1.0 - no_epidemic
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timing(sizes: &[usize]) -> EpidemicTiming {
+        EpidemicTiming::new(
+            sizes
+                .iter()
+                .map(|size| Pile {
+                    size: *size,
+                    has_epidemic: true,
+                })
+                .collect(),
+        )
+    }
+
+    fn close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn drawing_from_the_first_pile() {
+        let timing = timing(&[10, 10]);
+        close(timing.epidemic_chance(20, 0, 2), 0.2);
+        close(timing.epidemic_chance(20, 0, 10), 1.0);
+    }
+
+    #[test]
+    fn the_chance_grows_as_the_pile_runs_down() {
+        let timing = timing(&[10, 10]);
+        // 5 drawn from the top pile, none of them the Epidemic: it is in the other 5.
+        close(timing.epidemic_chance(15, 0, 2), 0.4);
+        close(timing.epidemic_chance(12, 0, 2), 1.0);
+    }
+
+    #[test]
+    fn no_epidemic_is_left_once_the_pile_has_shown_it() {
+        let timing = timing(&[10, 10]);
+        close(timing.epidemic_chance(17, 1, 2), 0.0);
+        // Reaching into the next pile: 7 cards left on top, then 2 of the 10 below.
+        close(timing.epidemic_chance(17, 1, 9), 0.2);
+    }
+
+    #[test]
+    fn draws_across_a_pile_boundary() {
+        let timing = timing(&[10, 10, 10]);
+        // 2 cards left on top with its Epidemic still in it: one of them is the Epidemic.
+        close(timing.epidemic_chance(22, 0, 2), 1.0);
+        // Top pile already showed its Epidemic; 1 card from it, then 1 of the next pile's 10.
+        close(timing.epidemic_chance(21, 1, 2), 0.1);
+        // The piles below the top one are untouched by the count of the ones above.
+        close(timing.epidemic_chance(10, 2, 3), 0.3);
+    }
+}
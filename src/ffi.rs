@@ -0,0 +1,317 @@
+use std::{
+    cell::RefCell,
+    ffi::{c_char, CStr, CString},
+    panic::{self, AssertUnwindSafe},
+    ptr, slice,
+};
+
+use crate::{
+    agent, cli, env,
+    game::{self, Seeded},
+    i18n::tr,
+    setup::{self, Difficulty},
+};
+
+/// How many action ids there are. New kinds of actions only ever get ids after the last one.
+/// Giving and taking a card name the other player by how many seats they sit after the one
+/// acting, so those ids pick a different player depending on whose turn it is.
+// Written out so the header has the number; `env::ACTIONS` lays the ids out.
+pub const PANDEMIC_ACTIONS: u32 = 598;
+const _: () = assert!(PANDEMIC_ACTIONS as usize == env::ACTIONS);
+
+/// What `pandemic_apply` returns when the library itself went wrong rather than the action.
+pub const PANDEMIC_INTERNAL_ERROR: i32 = -2;
+
+/// A game for programs written in other languages, through the C API in the generated
+/// `pandemic.h`. Strings handed out are the caller's to free with `pandemic_string_free`, and
+/// when a call fails, `pandemic_last_error` says why. A game may move between threads but
+/// must not be used by two at once.
+pub struct PandemicGame(Seeded);
+
+thread_local! {
+    static LAST_ERROR: RefCell<CString> = RefCell::new(CString::default());
+}
+
+fn fail(message: String) {
+    // Messages come from the catalogs, which never hold a NUL.
+    let message = CString::new(message).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = message);
+}
+
+/// Runs `body` without the game's commentary, so nothing reaches the caller's stdout, and
+/// returns `failed` instead of unwinding into C when it panics.
+fn guarded<T>(failed: T, body: impl FnOnce() -> T) -> T {
+    match panic::catch_unwind(AssertUnwindSafe(|| game::quietly(body))) {
+        Ok(result) => result,
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_default();
+            fail(tr!("ffi.panicked", message));
+            failed
+        }
+    }
+}
+
+/// # Safety
+///
+/// `pointer` is NULL or a NUL-terminated string.
+unsafe fn text(pointer: *const c_char) -> Option<String> {
+    if pointer.is_null() {
+        return None;
+    }
+    // SAFETY: the caller passes a NUL-terminated string.
+    let text = unsafe { CStr::from_ptr(pointer) };
+    Some(text.to_string_lossy().into_owned())
+}
+
+fn owned(text: String) -> *mut c_char {
+    CString::new(text).map_or(ptr::null_mut(), CString::into_raw)
+}
+
+/// Why the last call on this thread failed. The text stays valid until the next failing call.
+#[no_mangle]
+pub extern "C" fn pandemic_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ptr())
+}
+
+/// Deals a new game. `players` is a comma-separated list of names, each with an optional role
+/// after a colon, like `"Ana:Medic,Bo"`. `difficulty` may be NULL for the standard game.
+/// Returns NULL when the players or the difficulty are not valid.
+///
+/// # Safety
+///
+/// `players` and `difficulty` are each NULL or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn pandemic_new(
+    players: *const c_char,
+    difficulty: *const c_char,
+    seed: u64,
+) -> *mut PandemicGame {
+    guarded(ptr::null_mut(), || {
+        // SAFETY: the caller passes NULL or NUL-terminated strings.
+        let (players, difficulty) = unsafe { (text(players), text(difficulty)) };
+        let Some(players) = players else {
+            fail(tr!("ffi.players_required"));
+            return ptr::null_mut();
+        };
+        let players = match cli::parse_players(&players) {
+            Ok(players) => players,
+            Err(message) => {
+                fail(message);
+                return ptr::null_mut();
+            }
+        };
+        let difficulty = match difficulty {
+            None => Difficulty::Standard,
+            Some(name) => match Difficulty::parse(&name) {
+                Ok(difficulty) => difficulty,
+                Err(message) => {
+                    fail(message);
+                    return ptr::null_mut();
+                }
+            },
+        };
+        let seeded = Seeded::deal(&setup::deal_roles(&players, difficulty), seed);
+        Box::into_raw(Box::new(PandemicGame(seeded)))
+    })
+}
+
+/// Frees a game from `pandemic_new`. NULL is ignored.
+///
+/// # Safety
+///
+/// `game` is NULL or came from `pandemic_new`, has not been freed yet and is not used again.
+#[no_mangle]
+pub unsafe extern "C" fn pandemic_free(game: *mut PandemicGame) {
+    guarded((), || {
+        if !game.is_null() {
+            // SAFETY: the pointer came from `pandemic_new` and is not used again.
+            drop(unsafe { Box::from_raw(game) });
+        }
+    })
+}
+
+/// Frees a string from this library. NULL is ignored.
+///
+/// # Safety
+///
+/// `text` is NULL or a string this library handed out, not freed yet and not used again.
+#[no_mangle]
+pub unsafe extern "C" fn pandemic_string_free(text: *mut c_char) {
+    guarded((), || {
+        if !text.is_null() {
+            // SAFETY: the pointer came from `CString::into_raw` and is not used again.
+            drop(unsafe { CString::from_raw(text) });
+        }
+    })
+}
+
+/// Takes action `action` for the player the game is waiting on. Returns 0 when it was played,
+/// -1 when it is not legal now or `game` is NULL, and `PANDEMIC_INTERNAL_ERROR` when the
+/// library failed; the game should not be used after that.
+///
+/// # Safety
+///
+/// `game` is NULL or a live game from `pandemic_new` that no other thread is using.
+#[no_mangle]
+pub unsafe extern "C" fn pandemic_apply(game: *mut PandemicGame, action: u32) -> i32 {
+    guarded(PANDEMIC_INTERNAL_ERROR, || {
+        // SAFETY: the pointer came from `pandemic_new` and is not shared with another thread.
+        let Some(PandemicGame(hosted)) = (unsafe { game.as_mut() }) else {
+            fail(tr!("ffi.null_game"));
+            return -1;
+        };
+        let Some(seat) = hosted.game.waiting_on() else {
+            fail(tr!("env.over"));
+            return -1;
+        };
+        let Some(command) = env::command(&hosted.game, action as usize)
+            .filter(|command| hosted.game.legal_actions().contains(command))
+        else {
+            fail(tr!("env.illegal", action));
+            return -1;
+        };
+        match hosted.apply(seat, &command) {
+            Ok(()) => 0,
+            Err(message) => {
+                fail(message);
+                -1
+            }
+        }
+    })
+}
+
+/// Writes the ids of the legal actions, lowest first, into `actions`, up to `capacity` of
+/// them, and returns how many there are in all. None are legal once the game is over, and
+/// when `game` is NULL or the library failed it returns 0.
+///
+/// # Safety
+///
+/// `game` is NULL or a live game from `pandemic_new`. `actions` is NULL or has room for
+/// `capacity` ids.
+#[no_mangle]
+pub unsafe extern "C" fn pandemic_legal_actions(
+    game: *const PandemicGame,
+    actions: *mut u32,
+    capacity: usize,
+) -> usize {
+    guarded(0, || {
+        // SAFETY: the pointer came from `pandemic_new`.
+        let Some(PandemicGame(hosted)) = (unsafe { game.as_ref() }) else {
+            fail(tr!("ffi.null_game"));
+            return 0;
+        };
+        let legal: Vec<u32> = env::action_mask(&hosted.game)
+            .iter()
+            .enumerate()
+            .filter(|(_, legal)| **legal)
+            .map(|(id, _)| id as u32)
+            .collect();
+        if !actions.is_null() {
+            // SAFETY: the caller gives room for `capacity` ids at `actions`.
+            let out = unsafe { slice::from_raw_parts_mut(actions, capacity) };
+            let count = capacity.min(legal.len());
+            out[..count].copy_from_slice(&legal[..count]);
+        }
+        legal.len()
+    })
+}
+
+/// Action `action` as a JSON array of its kind and arguments, like `["drive", "Chicago"]`,
+/// with cities, colors and events by their names in the code whatever the language. NULL
+/// when it means nothing for the player the game is waiting on.
+///
+/// # Safety
+///
+/// `game` is NULL or a live game from `pandemic_new`.
+#[no_mangle]
+pub unsafe extern "C" fn pandemic_action_json(
+    game: *const PandemicGame,
+    action: u32,
+) -> *mut c_char {
+    guarded(ptr::null_mut(), || {
+        // SAFETY: the pointer came from `pandemic_new`.
+        let Some(PandemicGame(hosted)) = (unsafe { game.as_ref() }) else {
+            fail(tr!("ffi.null_game"));
+            return ptr::null_mut();
+        };
+        env::command(&hosted.game, action as usize).map_or(ptr::null_mut(), |command| {
+            owned(command.to_json().to_string())
+        })
+    })
+}
+
+/// The whole game as JSON, the same object the agent mode sends as `state`.
+///
+/// # Safety
+///
+/// `game` is NULL or a live game from `pandemic_new`.
+#[no_mangle]
+pub unsafe extern "C" fn pandemic_state_json(game: *const PandemicGame) -> *mut c_char {
+    guarded(ptr::null_mut(), || {
+        // SAFETY: the pointer came from `pandemic_new`.
+        let Some(PandemicGame(hosted)) = (unsafe { game.as_ref() }) else {
+            fail(tr!("ffi.null_game"));
+            return ptr::null_mut();
+        };
+        owned(agent::state(&hosted.game).to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn last_error() -> String {
+        // SAFETY: the text is NUL-terminated and lives until the next failing call.
+        unsafe { CStr::from_ptr(pandemic_last_error()) }
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn the_header_in_include_is_up_to_date() {
+        let built = include_str!(concat!(env!("OUT_DIR"), "/pandemic.h"));
+        assert!(
+            built == include_str!("../include/pandemic.h"),
+            "include/pandemic.h is out of date: copy {}/pandemic.h over it",
+            env!("OUT_DIR")
+        );
+    }
+
+    #[test]
+    fn a_null_game_is_an_error() {
+        // SAFETY: NULL is allowed everywhere.
+        unsafe {
+            assert_eq!(pandemic_apply(ptr::null_mut(), 0), -1);
+            assert_eq!(last_error(), tr!("ffi.null_game"));
+            assert_eq!(pandemic_legal_actions(ptr::null(), ptr::null_mut(), 0), 0);
+            assert!(pandemic_state_json(ptr::null()).is_null());
+        }
+    }
+
+    #[test]
+    fn actions_are_described_by_their_names_in_the_code() {
+        let players = CString::new("Ana:Medic,Bo:Scientist").unwrap();
+        // SAFETY: the strings are NUL-terminated and the game is freed once, at the end.
+        unsafe {
+            let game = pandemic_new(players.as_ptr(), ptr::null(), 7);
+            assert!(!game.is_null());
+            let mut actions = [0; PANDEMIC_ACTIONS as usize];
+            let count = pandemic_legal_actions(game, actions.as_mut_ptr(), actions.len());
+            assert!(count > 0);
+            let text = pandemic_action_json(game, actions[0]);
+            let described = CStr::from_ptr(text).to_string_lossy().into_owned();
+            pandemic_string_free(text);
+            let command = crate::json::parse(&described)
+                .ok()
+                .and_then(|json| crate::command::Command::from_json(&json));
+            assert_eq!(command, env::command(&(*game).0.game, actions[0] as usize));
+            assert_eq!(pandemic_apply(game, actions[0]), 0);
+            pandemic_free(game);
+        }
+    }
+}
@@ -0,0 +1,724 @@
+use std::{
+    cell::Cell,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{
+    board::{self, Board, Cities},
+    command::{find_pin, find_player, Command},
+    common::Color,
+    i18n::tr,
+    log::Entry,
+    player::{self, Player},
+    player_card::{Events, PlayerCard},
+    random,
+    setup::{self, Setup},
+};
+
+/// Set while stdout carries something other than the running commentary, like the agent
+/// protocol.
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+thread_local! {
+    /// Set while `quietly` runs on this thread.
+    static QUIET_HERE: Cell<bool> = const { Cell::new(false) };
+}
+
+pub fn quiet() -> bool {
+    QUIET.load(Ordering::Relaxed) || QUIET_HERE.with(Cell::get)
+}
+
+/// Runs `f` without the commentary, leaving other threads and later calls as they were.
+pub fn quietly<R>(f: impl FnOnce() -> R) -> R {
+    struct Restore(bool);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            QUIET_HERE.with(|quiet| quiet.set(self.0));
+        }
+    }
+    let _restore = Restore(QUIET_HERE.with(|quiet| quiet.replace(true)));
+    f()
+}
+
+/// `println!` for what happens during play, silenced by `set_quiet` and `quietly`.
+macro_rules! say {
+    ($($arg:tt)*) => {
+        if !$crate::game::quiet() {
+            println!($($arg)*);
+        }
+    };
+}
+
+pub(crate) use say;
+
+/// How a game ended.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Outcome {
+    Victory,
+    Outbreaks,
+    Cubes,
+    Cards,
+}
+
+impl std::fmt::Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let key = match self {
+            Self::Victory => "game.victory",
+            Self::Outbreaks => "game.over.outbreaks",
+            Self::Cubes => "game.over.cubes",
+            Self::Cards => "game.over.cards",
+        };
+        write!(f, "{}", tr!(key))
+    }
+}
+
+/// Why infecting failed: either the outbreaks marker ran off the track or a color ran out of
+/// cubes.
+fn lost(board: &Board) -> Outcome {
+    if board.outbreaks >= board::MAX_OUTBREAKS {
+        Outcome::Outbreaks
+    } else {
+        Outcome::Cubes
+    }
+}
+
+/// Builds the table for `setup`: shuffles, deals, seeds the epidemics and places the starting
+/// infections. Players are ordered by the most populous city in hand, which goes first.
+pub fn start(setup: &Setup) -> (Board, Vec<Player>) {
+    let mut players: Vec<Player> = setup
+        .players
+        .iter()
+        .map(|(name, role)| Player::new(name, *role))
+        .collect();
+
+    let mut board = Board::new();
+    board.player_deck.shuffle();
+    board.infection_deck.shuffle();
+
+    for _ in 0..setup::cards_per_player(players.len()) {
+        board.player_deck.deal(&mut players);
+    }
+
+    board.add_epidemic_cards(setup.difficulty.epidemics());
+
+    players.sort_by_key(|a| a.max_population_city());
+    players.reverse();
+
+    for player in &players {
+        say!("{}", &player);
+    }
+
+    say!("{}", tr!("setup.using_difficulty", setup.difficulty));
+
+    for i in 0..board::MAX_INFECTION_PER_TYPE_PER_CITY {
+        for _ in 0..board::NUMBER_OF_CITIES_TO_INFECT_PER_ROUND_AT_START {
+            if let Some(infection_card) = board.draw_infection_card() {
+                let quantity = board::MAX_INFECTION_PER_TYPE_PER_CITY - i;
+                let key = if quantity > 1 {
+                    "setup.infected.other"
+                } else {
+                    "setup.infected.one"
+                };
+                say!("{}", tr!(key, infection_card.city, quantity));
+                for _ in 0..quantity {
+                    board.infect_city(infection_card.city);
+                }
+                board.end_infection_step();
+                board.infection_discard.discard_to_top(infection_card);
+            }
+        }
+    }
+
+    (board, players)
+}
+
+/// The Draw Cards step: 2 Player cards, resolving any Epidemic straight away.
+pub fn draw_cards(board: &mut Board, player: &mut Player) -> Result<(), Outcome> {
+    for _ in 0..board::DRAW_CARDS_PER_ROUND {
+        match board.player_deck.draw_from_top() {
+            Some(PlayerCard::EpidemicCard) => {
+                say!("{}", tr!("turn.drew_epidemic", player.name()));
+                board.log.record(Entry::Drew(
+                    player.plain_name().to_owned(),
+                    PlayerCard::EpidemicCard,
+                ));
+                board.epidemics += 1;
+                board.increase_infection_rate();
+                if let Some(infection_card) = board.draw_epidemic_infection_card() {
+                    say!("{}", tr!("turn.epidemic_in", infection_card.city));
+                    board.log.record(Entry::Epidemic(infection_card.city));
+                    let mut game_continue = true;
+                    for _ in 0..board::MAX_INFECTION_PER_TYPE_PER_CITY {
+                        game_continue = board.infect_city(infection_card.city) && game_continue;
+                    }
+                    board.end_infection_step();
+                    if !game_continue {
+                        return Err(lost(board));
+                    }
+                    board.infection_discard.discard_to_top(infection_card);
+                    board.intensify();
+                }
+            }
+            Some(card) => {
+                say!("{}", tr!("turn.drew", player.name(), card));
+                board
+                    .log
+                    .record(Entry::Drew(player.plain_name().to_owned(), card));
+                player.add_to_hand(card)
+            }
+            None => return Err(Outcome::Cards),
+        }
+    }
+    Ok(())
+}
+
+/// The Infect Cities step: as many Infection cards as the infection rate.
+pub fn infect_cities(board: &mut Board) -> Result<(), Outcome> {
+    for _ in 0..board.infection_rate() {
+        if let Some(infection_card) = board.draw_infection_card() {
+            say!("{}", tr!("turn.infected", infection_card.city));
+            let game_continue = board.infect_city(infection_card.city);
+            board.end_infection_step();
+            board.infection_discard.discard_to_top(infection_card);
+            if !game_continue {
+                return Err(lost(board));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Keeps what `player` says or pins for the table in the log. Talking costs no actions and can
+/// happen on anyone's turn.
+pub fn talk(
+    board: &mut Board,
+    player: &str,
+    names: &[&str],
+    command: &Command,
+) -> Result<(), String> {
+    let entry = match command {
+        Command::Say(text) => Entry::Chat(player.to_owned(), text.clone()),
+        Command::Note(target, text) => {
+            Entry::Note(player.to_owned(), find_pin(target, names)?, text.clone())
+        }
+        _ => return Err(tr!("game.not_now")),
+    };
+    board.log.record(entry);
+    Ok(())
+}
+
+/// The kinds of Event card `player` holds, each once.
+fn held_events(player: &Player) -> Vec<Events> {
+    let mut events: Vec<Events> = player
+        .hand
+        .iter()
+        .filter_map(|card| match card {
+            PlayerCard::EventCard(event) => Some(event.event()),
+            _ => None,
+        })
+        .collect();
+    events.sort_unstable();
+    events.dedup();
+    events
+}
+
+/// What the game is waiting for.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Phase {
+    /// The current player is taking actions.
+    Actions,
+    /// `to` has been asked to agree to Share Knowledge with the current player, who gives
+    /// `card` when `giving` and takes it otherwise.
+    Offer {
+        to: usize,
+        card: Cities,
+        giving: bool,
+    },
+    /// `player` holds too many cards and has to discard or play Event cards. `drawn` says
+    /// whether this follows the Draw Cards step, so the Infect Cities step comes next.
+    Discard {
+        player: usize,
+        drawn: bool,
+    },
+    Over(Outcome),
+}
+
+/// A game driven by commands instead of prompts, for when the players are not at this
+/// terminal. Every player is known by their seat, their index in `players`.
+pub struct Game {
+    pub(crate) board: Board,
+    pub(crate) players: Vec<Player>,
+    pub(crate) current: usize,
+    pub(crate) actions_left: u8,
+    pub(crate) phase: Phase,
+}
+
+impl Game {
+    pub fn new(setup: &Setup) -> Self {
+        let (mut board, players) = start(setup);
+        board
+            .log
+            .record(Entry::Turn(players[0].plain_name().to_owned()));
+        Self {
+            board,
+            players,
+            current: 0,
+            actions_left: player::ACTIONS_PER_TURN,
+            phase: Phase::Actions,
+        }
+    }
+
+    /// The seat whose answer the game is waiting on, if any.
+    pub fn waiting_on(&self) -> Option<usize> {
+        match self.phase {
+            Phase::Actions => Some(self.current),
+            Phase::Offer { to, .. } => Some(to),
+            Phase::Discard { player, .. } => Some(player),
+            Phase::Over(_) => None,
+        }
+    }
+
+    /// Every command the awaited player could give now, in a fixed order. Walking a whole
+    /// route is left out, as it is only a series of the moves listed.
+    pub fn legal_actions(&self) -> Vec<Command> {
+        let Some(seat) = self.waiting_on() else {
+            return Vec::new();
+        };
+        let player = &self.players[seat];
+        let mut actions = Vec::new();
+        match self.phase {
+            Phase::Actions => {
+                let board = &self.board;
+                let here = player.location();
+                let mut cards = player.city_cards();
+                cards.sort_unstable();
+                let stations = board.research_stations();
+                actions.push(Command::Pass);
+                let mut adjacent = Vec::from_iter(board.adjacent_to(here).unwrap_or_default());
+                adjacent.sort_unstable();
+                actions.extend(adjacent.into_iter().map(Command::Drive));
+                actions.extend(
+                    cards
+                        .iter()
+                        .filter(|card| **card != here)
+                        .map(|card| Command::Fly(*card)),
+                );
+                if cards.contains(&here) {
+                    let mut cities = board.all_cities();
+                    cities.sort_unstable();
+                    actions.extend(
+                        cities
+                            .into_iter()
+                            .filter(|city| *city != here)
+                            .map(Command::Charter),
+                    );
+                }
+                if stations.contains(&here) {
+                    actions.extend(
+                        stations
+                            .iter()
+                            .filter(|city| **city != here)
+                            .map(|city| Command::Shuttle(*city)),
+                    );
+                }
+                if cards.contains(&here) && !stations.contains(&here) {
+                    if board.total_research_stations() < board::MAX_RESEARCH_STATIONS {
+                        actions.push(Command::Build(None));
+                    } else {
+                        actions.extend(stations.iter().map(|city| Command::Build(Some(*city))));
+                    }
+                }
+                actions.extend(
+                    Color::ALL
+                        .into_iter()
+                        .filter(|color| board.map[&here].infection_count(*color) > 0)
+                        .map(|color| Command::Treat(Some(color))),
+                );
+                for (i, other) in self.players.iter().enumerate() {
+                    if i == seat || other.location() != here {
+                        continue;
+                    }
+                    let name = other.plain_name().to_owned();
+                    for card in &cards {
+                        if player.can_give(other, *card) {
+                            actions.push(Command::Give(*card, name.clone()));
+                        }
+                    }
+                    let mut theirs = other.city_cards();
+                    theirs.sort_unstable();
+                    for card in theirs {
+                        if other.can_give(player, card) {
+                            actions.push(Command::Take(card, name.clone()));
+                        }
+                    }
+                }
+                actions.extend(
+                    Color::ALL
+                        .into_iter()
+                        .filter(|color| player.can_cure(board, *color))
+                        .map(Command::Cure),
+                );
+            }
+            Phase::Offer { .. } => actions.extend([Command::Accept, Command::Decline]),
+            Phase::Discard { .. } => {
+                let mut cards = player.city_cards();
+                cards.sort_unstable();
+                actions.extend(cards.into_iter().map(Command::Discard));
+                actions.extend(held_events(player).into_iter().map(Command::DiscardEvent));
+            }
+            Phase::Over(_) => {}
+        }
+        actions
+    }
+
+    /// Carries out `command` for the player in `seat`, or explains why it cannot be done now.
+    pub fn apply(&mut self, seat: usize, command: &Command) -> Result<(), String> {
+        if let Phase::Over(outcome) = self.phase {
+            return Err(outcome.to_string());
+        }
+        if let Command::Play(_) = command {
+            // Until Event cards do what they say, playing one is no rules action at all.
+            return Err(tr!("game.events_unplayable"));
+        }
+        if let Command::Say(_) | Command::Note(..) = command {
+            let names: Vec<&str> = self.players.iter().map(Player::plain_name).collect();
+            return talk(
+                &mut self.board,
+                self.players[seat].plain_name(),
+                &names,
+                command,
+            );
+        }
+        let waiting = self.waiting_on().unwrap_or(self.current);
+        if seat != waiting {
+            return Err(tr!("game.waiting_for", self.players[waiting].name()));
+        }
+        match (self.phase, command) {
+            (Phase::Offer { .. }, Command::Accept) => self.accept(),
+            (Phase::Offer { to, .. }, Command::Decline) => {
+                say!("{}", tr!("share.refused", self.players[to].name()));
+                self.phase = Phase::Actions;
+                Ok(())
+            }
+            (Phase::Discard { .. }, Command::Discard(city)) => self.discard(seat, *city),
+            (Phase::Discard { .. }, Command::DiscardEvent(event)) => {
+                self.discard_event(seat, *event)
+            }
+            (Phase::Actions, Command::Give(card, name) | Command::Take(card, name)) => {
+                self.offer(*card, name, matches!(command, Command::Give(..)))
+            }
+            (
+                Phase::Actions,
+                Command::Accept
+                | Command::Decline
+                | Command::Discard(_)
+                | Command::DiscardEvent(_)
+                | Command::Help(_)
+                | Command::Log
+                | Command::Status,
+            ) => Err(tr!("game.not_now")),
+            (Phase::Actions, Command::Travel(to)) => {
+                let actions_left = self.actions_left;
+                let used = self
+                    .with_current(|player, board, _| player.walk_route(board, *to, actions_left));
+                self.spend(used)
+            }
+            (Phase::Actions, Command::Build(from)) => {
+                let used = self.with_current(|player, board, _| player.build(board, *from) as u8);
+                self.spend(used)
+            }
+            (Phase::Actions, command) => {
+                let actions_left = self.actions_left;
+                let used = self.with_current(|player, board, others| {
+                    player.perform(board, others, command, actions_left)
+                });
+                self.spend(used)
+            }
+            _ => Err(tr!("game.not_now")),
+        }
+    }
+
+    /// Runs `f` on the current player with the others set apart, the way the turn menus see
+    /// them.
+    fn with_current<R>(
+        &mut self,
+        f: impl FnOnce(&mut Player, &mut Board, &mut [Player]) -> R,
+    ) -> R {
+        let mut player = self.players.remove(self.current);
+        let result = f(&mut player, &mut self.board, &mut self.players);
+        self.players.insert(self.current, player);
+        result
+    }
+
+    fn spend(&mut self, used: u8) -> Result<(), String> {
+        if used == 0 {
+            let player = &self.players[self.current];
+            return Err(tr!("command.illegal", player.name(), player.location()));
+        }
+        self.actions_left = self.actions_left.saturating_sub(used);
+        self.after_action();
+        Ok(())
+    }
+
+    fn after_action(&mut self) {
+        if self.board.all_cured() {
+            self.phase = Phase::Over(Outcome::Victory);
+        } else if self.actions_left == 0 {
+            self.end_actions();
+        } else {
+            self.phase = Phase::Actions;
+        }
+    }
+
+    fn offer(&mut self, card: Cities, name: &str, giving: bool) -> Result<(), String> {
+        let names: Vec<&str> = self
+            .players
+            .iter()
+            .map(|player| player.plain_name())
+            .collect();
+        let to = find_player(name, &names)?;
+        let (giver, receiver) = if giving {
+            (self.current, to)
+        } else {
+            (to, self.current)
+        };
+        if to == self.current || !self.players[giver].can_give(&self.players[receiver], card) {
+            let player = &self.players[self.current];
+            return Err(tr!("command.illegal", player.name(), player.location()));
+        }
+        self.phase = Phase::Offer { to, card, giving };
+        Ok(())
+    }
+
+    fn accept(&mut self) -> Result<(), String> {
+        let Phase::Offer { to, card, giving } = self.phase else {
+            return Err(tr!("game.not_now"));
+        };
+        let receiver = if giving { to } else { self.current };
+        let mut other = self.players.remove(to);
+        let current = if to < self.current {
+            self.current - 1
+        } else {
+            self.current
+        };
+        let player = &mut self.players[current];
+        let shared = if giving {
+            player.give(&mut self.board, &mut other, card)
+        } else {
+            player.take(&mut self.board, &mut other, card)
+        };
+        self.players.insert(to, other);
+        if !shared {
+            self.phase = Phase::Actions;
+            return Err(tr!("game.not_now"));
+        }
+        self.actions_left -= 1;
+        if self.players[receiver].hand.len() > player::MAX_CARDS_IN_HAND {
+            self.phase = Phase::Discard {
+                player: receiver,
+                drawn: false,
+            };
+        } else {
+            self.after_action();
+        }
+        Ok(())
+    }
+
+    fn discard(&mut self, seat: usize, city: Cities) -> Result<(), String> {
+        let player = &self.players[seat];
+        let Some(idx) = player
+            .hand
+            .iter()
+            .position(|card| matches!(card, PlayerCard::CityCard(held) if held.city == city))
+        else {
+            return Err(tr!("game.no_card", city));
+        };
+        self.discard_at(seat, idx);
+        Ok(())
+    }
+
+    /// Discards an Event card without playing it.
+    fn discard_event(&mut self, seat: usize, event: Events) -> Result<(), String> {
+        let player = &self.players[seat];
+        let Some(idx) = player
+            .hand
+            .iter()
+            .position(|card| matches!(card, PlayerCard::EventCard(held) if held.event() == event))
+        else {
+            return Err(tr!("game.no_card", event));
+        };
+        self.discard_at(seat, idx);
+        Ok(())
+    }
+
+    fn discard_at(&mut self, seat: usize, idx: usize) {
+        let player = &mut self.players[seat];
+        let card = player.hand.remove(idx);
+        self.board
+            .log
+            .record(Entry::Discarded(player.plain_name().to_owned(), card));
+        self.board.player_discard.discard_to_top(card);
+        self.after_hand_change(seat);
+    }
+
+    /// Moves on once the player who had to cut down their hand is back under the limit.
+    fn after_hand_change(&mut self, seat: usize) {
+        if let Phase::Discard { player, drawn } = self.phase {
+            if player == seat && self.players[seat].hand.len() <= player::MAX_CARDS_IN_HAND {
+                if drawn {
+                    self.infect_and_pass();
+                } else {
+                    self.after_action();
+                }
+            }
+        }
+    }
+
+    fn end_actions(&mut self) {
+        let current = self.current;
+        let drawn = draw_cards(&mut self.board, &mut self.players[current]);
+        if let Err(outcome) = drawn {
+            self.phase = Phase::Over(outcome);
+        } else if self.players[current].hand.len() > player::MAX_CARDS_IN_HAND {
+            self.phase = Phase::Discard {
+                player: current,
+                drawn: true,
+            };
+        } else {
+            self.infect_and_pass();
+        }
+    }
+
+    fn infect_and_pass(&mut self) {
+        if let Err(outcome) = infect_cities(&mut self.board) {
+            self.phase = Phase::Over(outcome);
+            return;
+        }
+        self.current = (self.current + 1) % self.players.len();
+        self.actions_left = player::ACTIONS_PER_TURN;
+        self.phase = Phase::Actions;
+        self.board.log.record(Entry::Turn(
+            self.players[self.current].plain_name().to_owned(),
+        ));
+    }
+}
+
+/// A game with its own random sequence, so a seed deals and plays out the same way however
+/// many other games run alongside it.
+pub struct Seeded {
+    pub(crate) game: Game,
+    pub(crate) seed: u64,
+    rng: StdRng,
+}
+
+impl Seeded {
+    pub fn deal(setup: &Setup, seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let game = random::using(&mut rng, || Game::new(setup));
+        Self { game, seed, rng }
+    }
+
+    /// A game dealt from `seed` and played on until its sequence reached `rng`.
+    pub fn resumed(game: Game, seed: u64, rng: StdRng) -> Self {
+        Self { game, seed, rng }
+    }
+
+    /// `Game::apply`, drawing from this game's sequence.
+    pub fn apply(&mut self, seat: usize, command: &Command) -> Result<(), String> {
+        let game = &mut self.game;
+        random::using(&mut self.rng, || game.apply(seat, command))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn infection_cards_go_to_the_discard_pile() {
+        let mut board = Board::new();
+        let deck = board.infection_deck.len();
+        let rate = board.infection_rate() as usize;
+        infect_cities(&mut board).unwrap();
+        assert_eq!(board.infection_deck.len(), deck - rate);
+        assert_eq!(board.infection_discard.len(), rate);
+    }
+
+    /// A game with Ana over the hand limit, holding `event` among her cards.
+    fn over_the_limit(event: Events) -> Game {
+        set_quiet(true);
+        let players = [
+            ("Ana".to_owned(), Some(crate::role::Role::Medic)),
+            ("Bo".to_owned(), None),
+        ];
+        let mut game = Game::new(&setup::deal_roles(
+            &players,
+            setup::Difficulty::Introductory,
+        ));
+        let seat = game
+            .players
+            .iter()
+            .position(|player| player.plain_name() == "Ana")
+            .unwrap();
+        let mut deck = crate::deck::Deck::<PlayerCard>::new();
+        let ana = &mut game.players[seat];
+        ana.hand.clear();
+        while let Some(card) = deck.draw_from_top() {
+            let wanted = match &card {
+                PlayerCard::EventCard(held) => held.event() == event,
+                PlayerCard::CityCard(_) => ana.city_cards().len() < player::MAX_CARDS_IN_HAND,
+                PlayerCard::EpidemicCard => false,
+            };
+            if wanted {
+                ana.add_to_hand(card);
+            }
+        }
+        game.current = seat;
+        game.phase = Phase::Discard {
+            player: seat,
+            drawn: false,
+        };
+        game
+    }
+
+    #[test]
+    fn an_event_card_can_be_discarded_without_playing_it() {
+        let mut game = over_the_limit(Events::Airlift);
+        let seat = game.current;
+        let discard = Command::DiscardEvent(Events::Airlift);
+        assert!(game.legal_actions().contains(&discard));
+        let play = Command::Play(Events::Airlift);
+        assert!(!game.legal_actions().contains(&play));
+        assert!(game.apply(seat, &play).is_err());
+
+        game.apply(seat, &discard).unwrap();
+        assert_eq!(game.players[seat].hand.len(), player::MAX_CARDS_IN_HAND);
+        assert!(game.players[seat]
+            .hand
+            .iter()
+            .all(|card| !matches!(card, PlayerCard::EventCard(_))));
+        assert!(matches!(
+            game.board.player_discard.iter().last(),
+            Some(PlayerCard::EventCard(held)) if held.event() == Events::Airlift
+        ));
+        assert_eq!(game.phase, Phase::Actions);
+    }
+
+    #[test]
+    fn discard_names_an_event_card_when_no_city_matches() {
+        assert_eq!(
+            crate::command::parse("discard forecast"),
+            Ok(Command::DiscardEvent(Events::Forecast))
+        );
+        assert_eq!(
+            crate::command::parse("discard lagos"),
+            Ok(Command::Discard(Cities::Lagos))
+        );
+    }
+}
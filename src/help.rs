@@ -0,0 +1,161 @@
+use crossterm::style::{Color as ConsoleColor, Stylize};
+
+use crate::{
+    command::{find_unique, normalize},
+    i18n::{all_texts, tr},
+    menu::menu_cancelable,
+    player::Player,
+    player_card::Events,
+    role::{Role, RoleCard},
+    theme::Themed,
+};
+
+const RULES: &str = include_str!("../README.md");
+
+/// A heading of the rules with everything under it, down to the next heading of the same or a
+/// higher level.
+struct Section {
+    level: usize,
+    title: &'static str,
+    lines: Vec<&'static str>,
+}
+
+fn sections() -> Vec<Section> {
+    let lines: Vec<&'static str> = RULES.lines().collect();
+    let heading = |line: &str| {
+        let level = line.chars().take_while(|ch| *ch == '#').count();
+        (level > 0 && line[level..].starts_with(' ')).then_some(level)
+    };
+    lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let level = heading(line)?;
+            let end = lines[i + 1..]
+                .iter()
+                .position(|other| heading(other).is_some_and(|other| other <= level))
+                .map_or(lines.len(), |offset| i + 1 + offset);
+            Some(Section {
+                level,
+                title: line[level..].trim(),
+                lines: lines[i + 1..end].to_vec(),
+            })
+        })
+        // The top heading is the project name and the rest of the README is the rules.
+        .filter(|section| section.level > 1)
+        .collect()
+}
+
+fn show_section(section: &Section) {
+    println!("{:=^94}", format!(" {} ", section.title));
+    for line in &section.lines {
+        let level = line.chars().take_while(|ch| *ch == '#').count();
+        if level > 0 {
+            println!("{}", line[level..].trim().bold().themed());
+        } else {
+            // Markdown emphasis reads fine without its markers.
+            println!("{}", line.replace(['_', '*'], ""));
+        }
+    }
+}
+
+fn show_role(role: Role) {
+    println!("{}", role);
+    for line in RoleCard::new(role).description {
+        println!("\t{}", line);
+    }
+}
+
+fn show_roles(player: &Player, others: &[Player]) {
+    println!("{:=^94}", format!(" {} ", tr!("help.roles")));
+    for member in std::iter::once(player).chain(others.iter()) {
+        println!("{}", tr!("help.player_role", member.name()));
+        show_role(member.role());
+    }
+}
+
+fn show_events() {
+    println!("{:=^94}", format!(" {} ", tr!("help.events")));
+    for event in Events::ALL {
+        show_event(event);
+    }
+}
+
+fn show_event(event: Events) {
+    println!("{}", event.to_string().bold().themed());
+    println!("\t{}", event.description());
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Topic {
+    Role(Role),
+    Event(Events),
+    Rules(usize),
+}
+
+/// Looks `input` up among role names, Event card names and rule headings, in any language.
+fn find_topic(input: &str, sections: &[Section]) -> Result<Topic, String> {
+    let mut candidates: Vec<(Topic, String)> = Vec::new();
+    for role in Role::ALL {
+        for name in all_texts(&format!("role.{}", role.key())) {
+            candidates.push((Topic::Role(role), normalize(name)));
+        }
+    }
+    for event in Events::ALL {
+        for name in all_texts(&format!("event.{:?}", event)) {
+            candidates.push((Topic::Event(event), normalize(name)));
+        }
+    }
+    for (i, section) in sections.iter().enumerate() {
+        candidates.push((Topic::Rules(i), normalize(section.title)));
+    }
+    find_unique(&tr!("help.kind"), input, &candidates, |topic| match topic {
+        Topic::Role(role) => role.name().to_owned(),
+        Topic::Event(event) => event.to_string(),
+        Topic::Rules(i) => sections[i].title.to_owned(),
+    })
+}
+
+/// Shows the rules section, role or Event card named by `topic`, or the help menu when `topic`
+/// is empty.
+pub fn show_help(player: &Player, others: &[Player], topic: &str) {
+    let sections = sections();
+    if !topic.trim().is_empty() {
+        match find_topic(topic, &sections) {
+            Ok(Topic::Role(role)) => show_role(role),
+            Ok(Topic::Event(event)) => show_event(event),
+            Ok(Topic::Rules(i)) => show_section(&sections[i]),
+            Err(error) => println!(
+                "{}: {}",
+                tr!("error").with(ConsoleColor::Red).bold().themed(),
+                error
+            ),
+        }
+        return;
+    }
+
+    // Only the top sections are listed; the typed `help <topic>` reaches the smaller ones.
+    let chapters: Vec<&Section> = sections
+        .iter()
+        .filter(|section| section.level == 2)
+        .collect();
+    let mut options = vec![
+        tr!("help.own_role", player.role()),
+        tr!("help.roles"),
+        tr!("help.events"),
+    ];
+    options.extend(
+        chapters
+            .iter()
+            .map(|section| tr!("help.rules", section.title)),
+    );
+    loop {
+        match menu_cancelable(&tr!("menu.help", player.name()), &options) {
+            0 => return,
+            1 => show_role(player.role()),
+            2 => show_roles(player, others),
+            3 => show_events(),
+            idx => show_section(chapters[idx - 4]),
+        }
+    }
+}
@@ -0,0 +1,345 @@
+use std::{
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    time::Duration,
+};
+
+use crate::{
+    agent, cli,
+    game::{self, Game, Phase, Seeded},
+    i18n::tr,
+    json::{self, Json},
+    setup::{self, Difficulty},
+};
+
+pub const DEFAULT_PORT: u16 = 8080;
+
+/// Requests bigger than this are refused rather than read.
+const MAX_BODY: usize = 64 * 1024;
+
+/// How much of the request line and headers is read before the request is refused.
+const MAX_HEAD: u64 = 8 * 1024;
+
+/// A game hosted over HTTP.
+struct Hosted {
+    seeded: Seeded,
+    difficulty: Difficulty,
+}
+
+struct Request {
+    method: String,
+    path: String,
+    query: String,
+    body: String,
+}
+
+/// A status code and the JSON sent with it.
+type Response = (u16, Json);
+
+fn error(status: u16, message: String) -> Response {
+    (status, Json::object([("error", message.into())]))
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        400 => "Bad Request",
+        404 => "Not Found",
+        409 => "Conflict",
+        413 => "Payload Too Large",
+        431 => "Request Header Fields Too Large",
+        _ => "Internal Server Error",
+    }
+}
+
+fn read_request(stream: &TcpStream) -> Result<Request, Response> {
+    let bad = |_| error(400, tr!("http.bad_request"));
+    let mut reader = BufReader::new(stream);
+    let mut head = (&mut reader).take(MAX_HEAD);
+    // A line cut short by the limit never ends, so it is the head that is too long.
+    let mut read_line = |line: &mut String| match head.read_line(line) {
+        Ok(_) if !line.ends_with('\n') && head.limit() == 0 => {
+            Err(error(431, tr!("http.head_too_large", MAX_HEAD)))
+        }
+        Ok(_) => Ok(()),
+        Err(read) => Err(bad(read)),
+    };
+    let mut line = String::new();
+    read_line(&mut line)?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err(error(400, tr!("http.bad_request")));
+    };
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let mut length = 0;
+    loop {
+        let mut header = String::new();
+        read_line(&mut header)?;
+        let header = header.trim();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| error(400, tr!("http.bad_request")))?;
+            }
+        }
+    }
+    if length > MAX_BODY {
+        return Err(error(413, tr!("http.too_large", MAX_BODY)));
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).map_err(bad)?;
+    Ok(Request {
+        method: method.to_owned(),
+        path: path.to_owned(),
+        query: query.to_owned(),
+        body: String::from_utf8(body).map_err(|_| error(400, tr!("http.bad_request")))?,
+    })
+}
+
+fn respond(mut stream: &TcpStream, (status, body): &Response) -> io::Result<()> {
+    let body = format!("{}\n", body);
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\n\
+         Content-Type: application/json\r\n\
+         Content-Length: {}\r\n\
+         Connection: close\r\n\r\n{}",
+        status,
+        reason(*status),
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+/// Reads `{"players": [{"name": "Ana", "role": "Medic"}, {"name": "Bo"}], "seed": 42,
+/// "difficulty": "heroic"}`. Only the players are required. Seeds past 2^53 are given as strings.
+fn new_game(body: &str) -> Result<Hosted, String> {
+    let request = json::parse(body).map_err(|error| tr!("agent.invalid_json", error))?;
+    let entries = match request.get("players") {
+        Some(Json::Array(entries)) => entries
+            .iter()
+            .map(|entry| match entry.get("name").and_then(Json::as_str) {
+                Some(name) => Ok((name, entry.get("role").and_then(Json::as_str))),
+                None => Err(tr!("http.players_required")),
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+        _ => return Err(tr!("http.players_required")),
+    };
+    let players = cli::check_players(entries)?;
+    let difficulty = match request.get("difficulty") {
+        None | Some(Json::Null) => Difficulty::Standard,
+        Some(Json::String(name)) => Difficulty::parse(name)?,
+        Some(name) => Difficulty::parse(&name.to_string())?,
+    };
+    let seed = match request.get("seed") {
+        None | Some(Json::Null) => rand::random::<u64>() >> 11,
+        Some(Json::String(seed)) => seed.parse().map_err(|_| tr!("cli.invalid_seed", seed))?,
+        Some(seed) => seed
+            .as_usize()
+            .map(|seed| seed as u64)
+            .filter(|seed| *seed <= MAX_EXACT)
+            .ok_or_else(|| tr!("cli.invalid_seed", seed))?,
+    };
+    Ok(Hosted {
+        seeded: Seeded::deal(&setup::deal_roles(&players, difficulty), seed),
+        difficulty,
+    })
+}
+
+/// The largest whole number a JSON number holds exactly in most parsers.
+const MAX_EXACT: u64 = 1 << 53;
+
+/// `seed` as a number, or as a string when a number would round it.
+fn seed(seed: u64) -> Json {
+    match seed {
+        seed if seed <= MAX_EXACT => seed.into(),
+        seed => seed.to_string().into(),
+    }
+}
+
+fn phase(game: &Game) -> &'static str {
+    match game.phase {
+        Phase::Actions => "actions",
+        Phase::Offer { .. } => "offer",
+        Phase::Discard { .. } => "discard",
+        Phase::Over(_) => "over",
+    }
+}
+
+/// Log entries as text, from the `since`-th on when the query asks for `?since=N`.
+fn events(game: &Game, query: &str) -> Json {
+    let since = query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("since="))
+        .and_then(|since| since.parse().ok())
+        .unwrap_or(0);
+    let entries = game.board.log.entries();
+    Json::object([
+        ("total", entries.len().into()),
+        (
+            "events",
+            Json::array(
+                entries[since.min(entries.len())..]
+                    .iter()
+                    .map(|entry| entry.to_string()),
+            ),
+        ),
+    ])
+}
+
+#[derive(Default)]
+struct Server {
+    /// Game `n` is at `/games/n`, counting from 1.
+    games: Vec<Hosted>,
+}
+
+impl Server {
+    fn summary(&self, id: usize) -> Json {
+        let hosted = &self.games[id - 1];
+        Json::object([
+            ("id", id.into()),
+            ("seed", seed(hosted.seeded.seed)),
+            ("difficulty", hosted.difficulty.key().into()),
+            (
+                "players",
+                Json::array(
+                    hosted
+                        .seeded
+                        .game
+                        .players
+                        .iter()
+                        .map(|player| player.plain_name().to_owned()),
+                ),
+            ),
+            ("phase", phase(&hosted.seeded.game).into()),
+        ])
+    }
+
+    fn route(&mut self, request: &Request) -> Response {
+        let segments: Vec<&str> = request
+            .path
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .collect();
+        let method = request.method.as_str();
+        match (method, segments.as_slice()) {
+            ("GET", ["games"]) => (
+                200,
+                Json::Array((1..=self.games.len()).map(|id| self.summary(id)).collect()),
+            ),
+            ("POST", ["games"]) => match new_game(&request.body) {
+                Ok(hosted) => {
+                    self.games.push(hosted);
+                    let id = self.games.len();
+                    let mut created = self.summary(id);
+                    if let Json::Object(fields) = &mut created {
+                        fields.push((
+                            "state".into(),
+                            agent::state(&self.games[id - 1].seeded.game),
+                        ));
+                    }
+                    (201, created)
+                }
+                Err(message) => error(400, message),
+            },
+            (_, ["games", id, rest @ ..]) => {
+                let Some(id) = id
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|id| (1..=self.games.len()).contains(id))
+                else {
+                    return error(404, tr!("http.no_game", id));
+                };
+                let hosted = &mut self.games[id - 1].seeded;
+                match (method, rest) {
+                    ("GET", [] | ["state"]) => (200, agent::state(&hosted.game)),
+                    ("GET", ["actions"]) => (200, agent::legal_actions(&hosted.game)),
+                    ("GET", ["log"]) => (200, events(&hosted.game, &request.query)),
+                    ("POST", ["actions"]) => {
+                        let (seat, command) = match agent::request(&hosted.game, &request.body) {
+                            Ok(request) => request,
+                            Err(message) => return error(400, message),
+                        };
+                        let logged = hosted.game.board.log.entries().len();
+                        if let Err(message) = hosted.apply(seat, &command) {
+                            return error(409, message);
+                        }
+                        let game = &hosted.game;
+                        let entries = &game.board.log.entries()[logged..];
+                        (
+                            200,
+                            Json::object([
+                                ("state", agent::state(game)),
+                                (
+                                    "events",
+                                    Json::array(entries.iter().map(|entry| entry.to_string())),
+                                ),
+                                ("legal_actions", agent::legal_actions(game)),
+                            ]),
+                        )
+                    }
+                    _ => error(404, tr!("http.not_found", method, request.path)),
+                }
+            }
+            _ => error(404, tr!("http.not_found", method, request.path)),
+        }
+    }
+}
+
+/// Hosts games as JSON resources on localhost, for dashboards and other tools:
+///
+/// - `GET /games` and `POST /games` list the games and start a new one,
+/// - `GET /games/{id}/state` and `GET /games/{id}/actions` show a game and its legal actions,
+/// - `POST /games/{id}/actions` takes `{"action": N}` or `{"command": "...", "seat": n}`,
+/// - `GET /games/{id}/log` lists what happened, from the `?since=N`-th entry if asked.
+pub fn serve(port: u16) -> io::Result<()> {
+    game::set_quiet(true);
+    let listener = TcpListener::bind(("127.0.0.1", port))?;
+    println!("{}", tr!("http.listening", listener.local_addr()?));
+    let mut server = Server::default();
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else { continue };
+        // A client that stops halfway must not hold up everyone else.
+        if let Err(error) = stream.set_read_timeout(Some(Duration::from_secs(10))) {
+            eprintln!("{}", error);
+            continue;
+        }
+        let response = match read_request(&stream) {
+            Ok(request) => {
+                let response = server.route(&request);
+                println!("{} {} {}", request.method, request.path, response.0);
+                response
+            }
+            Err(response) => response,
+        };
+        // The client may already have gone; that is no reason to stop serving.
+        let _ = respond(&stream, &response);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAYERS: &str = r#""players": [{"name": "Ana"}, {"name": "Bo"}]"#;
+
+    #[test]
+    fn seeds_past_exact_numbers_come_as_strings() {
+        let big = u64::MAX - 1;
+        let hosted = new_game(&format!(r#"{{{}, "seed": "{}"}}"#, PLAYERS, big)).unwrap();
+        assert_eq!(hosted.seeded.seed, big);
+        assert_eq!(seed(big), Json::String(big.to_string()));
+        let hosted = new_game(&format!(r#"{{{}, "seed": {}}}"#, PLAYERS, MAX_EXACT)).unwrap();
+        assert_eq!(seed(hosted.seeded.seed), Json::Number(MAX_EXACT as f64));
+        assert!(new_game(&format!(r#"{{{}, "seed": 18446744073709551614}}"#, PLAYERS)).is_err());
+        assert!(new_game(&format!(r#"{{{}, "seed": "-1"}}"#, PLAYERS)).is_err());
+    }
+}
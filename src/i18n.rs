@@ -0,0 +1,173 @@
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        OnceLock,
+    },
+};
+
+/// A language the game can be played in, with its message catalog from `locales/`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Locale {
+    English,
+    Spanish,
+}
+
+/// Index into `Locale::ALL` of the active locale.
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+
+static CATALOGS: OnceLock<Vec<HashMap<String, String>>> = OnceLock::new();
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::English, Locale::Spanish];
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::English => "en",
+            Self::Spanish => "es",
+        }
+    }
+
+    fn source(&self) -> &'static str {
+        match self {
+            Self::English => include_str!("../locales/en.txt"),
+            Self::Spanish => include_str!("../locales/es.txt"),
+        }
+    }
+
+    /// Accepts a bare code like `es` or a POSIX locale like `es_ES.UTF-8`.
+    pub fn from_code(code: &str) -> Option<Self> {
+        let language = code
+            .split(['_', '.', '-', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|locale| locale.code() == language)
+    }
+
+    /// Uses `--lang <code>` when given, otherwise the first of `LC_ALL`, `LC_MESSAGES` and
+    /// `LANG` that is set, falling back to English.
+    pub fn detect() -> Self {
+        let args: Vec<String> = std::env::args().collect();
+        for (i, arg) in args.iter().enumerate() {
+            let code = match arg.strip_prefix("--lang=") {
+                Some(code) => Some(code),
+                None if arg == "--lang" => args.get(i + 1).map(|code| code.as_str()),
+                None => None,
+            };
+            if let Some(locale) = code.and_then(Self::from_code) {
+                return locale;
+            }
+        }
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Self::from_code(&value))
+            .unwrap_or(Self::English)
+    }
+
+    pub fn current() -> Self {
+        Self::ALL[CURRENT.load(Ordering::Relaxed)]
+    }
+
+    pub fn apply(self) {
+        let idx = Self::ALL
+            .iter()
+            .position(|locale| *locale == self)
+            .unwrap_or(0);
+        CURRENT.store(idx, Ordering::Relaxed);
+    }
+}
+
+/// Reads `key = value` lines, skipping blanks and `#` comments. `\n` in a value is a line break.
+fn parse(source: &str) -> HashMap<String, String> {
+    source
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim().to_owned(), value.trim().replace("\\n", "\n")))
+        .collect()
+}
+
+fn catalogs() -> &'static [HashMap<String, String>] {
+    CATALOGS.get_or_init(|| {
+        Locale::ALL
+            .iter()
+            .map(|locale| parse(locale.source()))
+            .collect()
+    })
+}
+
+fn lookup(locale: Locale, key: &str) -> Option<&'static str> {
+    let idx = Locale::ALL.iter().position(|other| *other == locale)?;
+    catalogs()[idx].get(key).map(|value| value.as_str())
+}
+
+/// The message for `key` in the current locale, or `fallback` when no catalog has it.
+pub fn text_or(key: &str, fallback: &'static str) -> &'static str {
+    lookup(Locale::current(), key)
+        .or_else(|| lookup(Locale::English, key))
+        .unwrap_or(fallback)
+}
+
+/// The message for `key` in the current locale, falling back to English.
+pub fn text(key: &str) -> &'static str {
+    text_or(key, "???")
+}
+
+/// The message for `key` in every locale, so typed input can be matched in any language.
+pub fn all_texts(key: &str) -> Vec<&'static str> {
+    let mut texts: Vec<&'static str> = Locale::ALL
+        .iter()
+        .filter_map(|locale| lookup(*locale, key))
+        .collect();
+    texts.dedup();
+    texts
+}
+
+/// Replaces `{}` with the next argument and `{N}` with argument N.
+pub fn fill(template: &str, args: &[&dyn Display]) -> String {
+    let mut result = String::new();
+    let mut next = 0;
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        result.push_str(&rest[..start]);
+        let inside = &rest[start + 1..start + end];
+        let idx = if inside.is_empty() {
+            next += 1;
+            Some(next - 1)
+        } else {
+            inside.parse::<usize>().ok()
+        };
+        match idx.and_then(|idx| args.get(idx)) {
+            Some(arg) => result.push_str(&arg.to_string()),
+            None => result.push_str(&rest[start..start + end + 1]),
+        }
+        rest = &rest[start + end + 1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Looks up a message in the catalog, filling in any arguments.
+macro_rules! tr {
+    ($key:expr) => {
+        $crate::i18n::text($key).to_owned()
+    };
+    ($key:expr, $($arg:expr),+ $(,)?) => {
+        $crate::i18n::fill(
+            $crate::i18n::text($key),
+            &[$(&$arg as &dyn std::fmt::Display),+],
+        )
+    };
+}
+
+pub(crate) use tr;
@@ -0,0 +1,211 @@
+use crossterm::style::{Color as ConsoleColor, Stylize};
+
+use crate::{
+    board::{self, Board, Cities, DiseaseState},
+    deck::Deck,
+    i18n::tr,
+    infection_card::InfectionCard,
+    theme::Themed,
+};
+
+/// What the table can know about the order of the Infection Deck by counting cards.
+///
+/// The deck is kept as a stack of layers, bottom first like `Deck`. The order of the cards
+/// inside a layer is unknown, but each layer is known to sit above the ones before it: every
+/// Intensify step puts the reshuffled discard pile on top as a new layer.
+#[derive(Debug)]
+pub struct InfectionKnowledge {
+    layers: Vec<Vec<Cities>>,
+}
+
+impl InfectionKnowledge {
+    pub fn new(deck: &Deck<InfectionCard>) -> Self {
+        Self {
+            layers: vec![deck.iter().map(|card| card.city).collect()],
+        }
+    }
+
+    pub fn draw_from_top(&mut self, city: Cities) {
+        for layer in self.layers.iter_mut().rev() {
+            if let Some(idx) = layer.iter().position(|x| *x == city) {
+                layer.swap_remove(idx);
+                break;
+            }
+        }
+        self.layers.retain(|layer| !layer.is_empty());
+    }
+
+    pub fn draw_from_bottom(&mut self, city: Cities) {
+        for layer in self.layers.iter_mut() {
+            if let Some(idx) = layer.iter().position(|x| *x == city) {
+                layer.swap_remove(idx);
+                break;
+            }
+        }
+        self.layers.retain(|layer| !layer.is_empty());
+    }
+
+    pub fn place_on_top(&mut self, cards: &Deck<InfectionCard>) {
+        if !cards.is_empty() {
+            self.layers
+                .push(cards.iter().map(|card| card.city).collect());
+        }
+    }
+
+    /// Number of cards on top of the deck whose identity (but not order) is known.
+    pub fn known_on_top(&self) -> usize {
+        self.layers.iter().skip(1).map(|layer| layer.len()).sum()
+    }
+
+    /// Chance of each city in the deck coming up within the next `draws` cards.
+    pub fn draw_odds(&self, draws: usize) -> Vec<(Cities, f64)> {
+        let mut remaining = draws;
+        let mut odds = Vec::new();
+        for layer in self.layers.iter().rev() {
+            let drawn = remaining.min(layer.len());
+            for city in layer {
+                odds.push((*city, drawn as f64 / layer.len() as f64));
+            }
+            remaining -= drawn;
+        }
+        odds
+    }
+}
+
+pub fn show_infection_risk(board: &Board) {
+    let draws = board.infection_rate() as usize;
+    let mut odds: Vec<(Cities, f64, u8)> = board
+        .infection_knowledge
+        .draw_odds(draws)
+        .into_iter()
+        .filter(|(_, chance)| *chance > 0.0)
+        .map(|(city, chance)| {
+            let city_obj = board.map.get(&city).unwrap();
+            (city, chance, city_obj.infection_count(city_obj.color))
+        })
+        .collect();
+    odds.sort_by(|a, b| b.1.total_cmp(&a.1).then(b.2.cmp(&a.2)).then(a.0.cmp(&b.0)));
+
+    println!("{:=^94}", format!(" {} ", tr!("risk.title")));
+    println!(
+        "{}",
+        tr!(
+            "risk.summary",
+            draws,
+            board.infection_knowledge.known_on_top()
+        )
+    );
+    if odds.is_empty() {
+        println!("\t{}", tr!("risk.empty"));
+    }
+    for (city, chance, count) in odds {
+        let city_obj = board.map.get(&city).unwrap();
+        let line = format!(
+            "{:>5.1}% {} ({}/{})",
+            chance * 100.0,
+            city,
+            count,
+            board::MAX_INFECTION_PER_TYPE_PER_CITY
+        );
+        if count >= board::MAX_INFECTION_PER_TYPE_PER_CITY
+            && board.disease_state(city_obj.color) != DiseaseState::Eradicated
+        {
+            println!(
+                "\t{} {}",
+                line,
+                tr!("risk.outbreak").with(ConsoleColor::Red).bold().themed()
+            );
+        } else {
+            println!("\t{}", line);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn knowledge(layers: Vec<Vec<Cities>>) -> InfectionKnowledge {
+        InfectionKnowledge { layers }
+    }
+
+    fn chance(odds: &[(Cities, f64)], city: Cities) -> f64 {
+        odds.iter().find(|(other, _)| *other == city).unwrap().1
+    }
+
+    #[test]
+    fn an_unknown_deck_gives_every_city_the_same_odds() {
+        let cities = vec![
+            Cities::Atlanta,
+            Cities::Chicago,
+            Cities::Essen,
+            Cities::Lima,
+        ];
+        let odds = knowledge(vec![cities.clone()]).draw_odds(2);
+        assert_eq!(odds.len(), cities.len());
+        for city in cities {
+            assert_eq!(chance(&odds, city), 0.5);
+        }
+    }
+
+    #[test]
+    fn the_top_layer_is_drawn_before_the_rest() {
+        let knowledge = knowledge(vec![
+            vec![
+                Cities::Atlanta,
+                Cities::Chicago,
+                Cities::Essen,
+                Cities::Lima,
+            ],
+            vec![Cities::Tokyo, Cities::Osaka],
+        ]);
+        assert_eq!(knowledge.known_on_top(), 2);
+
+        let odds = knowledge.draw_odds(1);
+        assert_eq!(chance(&odds, Cities::Tokyo), 0.5);
+        assert_eq!(chance(&odds, Cities::Atlanta), 0.0);
+
+        // Drawing past the top layer reaches into the one below it.
+        let odds = knowledge.draw_odds(3);
+        assert_eq!(chance(&odds, Cities::Osaka), 1.0);
+        assert_eq!(chance(&odds, Cities::Lima), 0.25);
+    }
+
+    #[test]
+    fn draws_are_taken_from_the_layer_they_came_from() {
+        let mut knowledge = knowledge(vec![
+            vec![Cities::Atlanta, Cities::Tokyo],
+            vec![Cities::Tokyo, Cities::Osaka],
+        ]);
+        knowledge.draw_from_bottom(Cities::Tokyo);
+        assert_eq!(knowledge.layers[0], vec![Cities::Atlanta]);
+        knowledge.draw_from_top(Cities::Tokyo);
+        assert_eq!(knowledge.layers[1], vec![Cities::Osaka]);
+        knowledge.draw_from_top(Cities::Osaka);
+        assert_eq!(knowledge.layers.len(), 1);
+    }
+
+    #[test]
+    fn intensify_puts_the_discard_pile_on_top_as_a_known_layer() {
+        let mut board = Board::new();
+        let mut drawn = Vec::new();
+        for _ in 0..4 {
+            let card = board.draw_infection_card().unwrap();
+            drawn.push(card.city);
+            board.infection_discard.discard_to_top(card);
+        }
+        board.intensify();
+        assert_eq!(board.infection_knowledge.known_on_top(), 4);
+
+        let odds = board.infection_knowledge.draw_odds(2);
+        for (city, odds) in &odds {
+            let expected = if drawn.contains(city) { 0.5 } else { 0.0 };
+            assert_eq!(*odds, expected, "{:?}", city);
+        }
+
+        // The cards really are on top: the next draw is one of them.
+        let next = board.draw_infection_card().unwrap();
+        assert!(drawn.contains(&next.city));
+        assert_eq!(board.infection_knowledge.known_on_top(), 3);
+    }
+}
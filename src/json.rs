@@ -0,0 +1,419 @@
+use std::fmt::Write;
+
+/// Just enough JSON for the machine-readable modes: building replies and reading requests.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    /// Keys stay in the order they were added, so replies read the same every time.
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    pub fn object<K: Into<String>>(fields: impl IntoIterator<Item = (K, Json)>) -> Self {
+        Self::Object(
+            fields
+                .into_iter()
+                .map(|(key, value)| (key.into(), value))
+                .collect(),
+        )
+    }
+
+    pub fn array<T: Into<Json>>(items: impl IntoIterator<Item = T>) -> Self {
+        Self::Array(items.into_iter().map(Into::into).collect())
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Self::Object(fields) => fields
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    /// The value as a whole number that is not negative.
+    pub fn as_usize(&self) -> Option<usize> {
+        match self {
+            Self::Number(n) if *n >= 0.0 && n.fract() == 0.0 => Some(*n as usize),
+            _ => None,
+        }
+    }
+}
+
+impl From<bool> for Json {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<&str> for Json {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_owned())
+    }
+}
+
+impl From<String> for Json {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+macro_rules! from_number {
+    ($($ty:ty),*) => {
+        $(impl From<$ty> for Json {
+            fn from(value: $ty) -> Self {
+                Self::Number(value as f64)
+            }
+        })*
+    };
+}
+
+from_number!(u8, u16, u32, u64, usize, i32, i64, f64);
+
+impl<T: Into<Json>> From<Option<T>> for Json {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Self::Null, Into::into)
+    }
+}
+
+fn write_string(f: &mut std::fmt::Formatter<'_>, text: &str) -> std::fmt::Result {
+    f.write_char('"')?;
+    for ch in text.chars() {
+        match ch {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            ch if (ch as u32) < 0x20 => write!(f, "\\u{:04x}", ch as u32)?,
+            ch => f.write_char(ch)?,
+        }
+    }
+    f.write_char('"')
+}
+
+/// Compact JSON on a single line.
+impl std::fmt::Display for Json {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Null => f.write_str("null"),
+            Self::Bool(value) => write!(f, "{}", value),
+            Self::Number(n) if n.is_finite() => write!(f, "{}", n),
+            Self::Number(_) => f.write_str("null"),
+            Self::String(text) => write_string(f, text),
+            Self::Array(items) => {
+                f.write_char('[')?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(',')?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                f.write_char(']')
+            }
+            Self::Object(fields) => {
+                f.write_char('{')?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(',')?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                f.write_char('}')
+            }
+        }
+    }
+}
+
+/// How deep arrays and objects may nest before the input is refused rather than read.
+const MAX_DEPTH: usize = 64;
+
+struct Parser<'a> {
+    chars: std::iter::Peekable<std::str::CharIndices<'a>>,
+    /// How many arrays and objects the parser is inside.
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|(_, ch)| ch.is_whitespace()).is_some() {}
+    }
+
+    fn error(&mut self) -> String {
+        match self.chars.peek() {
+            Some((at, ch)) => format!("unexpected '{}' at {}", ch, at),
+            None => "unexpected end of input".to_owned(),
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        self.skip_whitespace();
+        match self.chars.next_if(|(_, ch)| *ch == expected) {
+            Some(_) => Ok(()),
+            None => Err(self.error()),
+        }
+    }
+
+    fn word(&mut self, word: &str, value: Json) -> Result<Json, String> {
+        for expected in word.chars() {
+            if self.chars.next_if(|(_, ch)| *ch == expected).is_none() {
+                return Err(self.error());
+            }
+        }
+        Ok(value)
+    }
+
+    /// An array or object, read by `read` once its opening bracket is taken.
+    fn nested(&mut self, read: fn(&mut Self) -> Result<Json, String>) -> Result<Json, String> {
+        if self.depth == MAX_DEPTH {
+            return Err(format!("nested deeper than {} levels", MAX_DEPTH));
+        }
+        self.chars.next();
+        self.depth += 1;
+        let value = read(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn array(&mut self) -> Result<Json, String> {
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.chars.next_if(|(_, ch)| *ch == ']').is_some() {
+            return Ok(Json::Array(items));
+        }
+        loop {
+            items.push(self.value()?);
+            self.skip_whitespace();
+            match self.chars.next() {
+                Some((_, ',')) => {}
+                Some((_, ']')) => return Ok(Json::Array(items)),
+                _ => return Err(self.error()),
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<Json, String> {
+        let mut fields = Vec::new();
+        self.skip_whitespace();
+        if self.chars.next_if(|(_, ch)| *ch == '}').is_some() {
+            return Ok(Json::Object(fields));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.expect(':')?;
+            fields.push((key, self.value()?));
+            self.skip_whitespace();
+            match self.chars.next() {
+                Some((_, ',')) => {}
+                Some((_, '}')) => return Ok(Json::Object(fields)),
+                _ => return Err(self.error()),
+            }
+        }
+    }
+
+    fn value(&mut self) -> Result<Json, String> {
+        self.skip_whitespace();
+        match self.chars.peek().map(|(_, ch)| *ch) {
+            Some('n') => self.word("null", Json::Null),
+            Some('t') => self.word("true", Json::Bool(true)),
+            Some('f') => self.word("false", Json::Bool(false)),
+            Some('"') => self.string().map(Json::String),
+            Some('[') => self.nested(Self::array),
+            Some('{') => self.nested(Self::object),
+            Some(ch) if ch == '-' || ch.is_ascii_digit() => {
+                let mut number = String::new();
+                while let Some((_, ch)) = self
+                    .chars
+                    .next_if(|(_, ch)| ch.is_ascii_digit() || "+-.eE".contains(*ch))
+                {
+                    number.push(ch);
+                }
+                number
+                    .parse()
+                    .map(Json::Number)
+                    .map_err(|_| format!("invalid number '{}'", number))
+            }
+            _ => Err(self.error()),
+        }
+    }
+
+    /// The four hex digits after `\\u`.
+    fn code_unit(&mut self) -> Result<u32, String> {
+        let mut code = 0;
+        for _ in 0..4 {
+            match self.chars.next_if(|(_, ch)| ch.is_ascii_hexdigit()) {
+                Some((_, ch)) => code = code * 16 + ch.to_digit(16).unwrap_or_default(),
+                None => return Err(self.error()),
+            }
+        }
+        Ok(code)
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+        let mut text = String::new();
+        loop {
+            match self.chars.next() {
+                Some((_, '"')) => return Ok(text),
+                Some((_, '\\')) => match self.chars.next() {
+                    Some((_, 'n')) => text.push('\n'),
+                    Some((_, 'r')) => text.push('\r'),
+                    Some((_, 't')) => text.push('\t'),
+                    Some((_, 'b')) => text.push('\u{8}'),
+                    Some((_, 'f')) => text.push('\u{c}'),
+                    Some((_, 'u')) => {
+                        let code = self.code_unit()?;
+                        // Characters outside the Basic Multilingual Plane come as a pair of
+                        // UTF-16 surrogates, like "\uD83D\uDE00" for one emoji.
+                        let code = if (0xD800..0xDC00).contains(&code)
+                            && self.chars.next_if(|(_, ch)| *ch == '\\').is_some()
+                        {
+                            if self.chars.next_if(|(_, ch)| *ch == 'u').is_none() {
+                                return Err(self.error());
+                            }
+                            match self.code_unit()? {
+                                low @ 0xDC00..0xE000 => {
+                                    0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00)
+                                }
+                                _ => return Err("unpaired surrogate in '\\u' escape".to_owned()),
+                            }
+                        } else {
+                            code
+                        };
+                        text.push(
+                            char::from_u32(code)
+                                .ok_or_else(|| "unpaired surrogate in '\\u' escape".to_owned())?,
+                        );
+                    }
+                    Some((_, ch @ ('"' | '\\' | '/'))) => text.push(ch),
+                    Some((at, ch)) => return Err(format!("invalid escape '\\{}' at {}", ch, at)),
+                    None => return Err(self.error()),
+                },
+                Some((_, ch)) => text.push(ch),
+                None => return Err(self.error()),
+            }
+        }
+    }
+}
+
+/// Reads one JSON value, which has to make up all of `input`.
+pub fn parse(input: &str) -> Result<Json, String> {
+    let mut parser = Parser {
+        chars: input.char_indices().peekable(),
+        depth: 0,
+    };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    match parser.chars.peek() {
+        None => Ok(value),
+        Some(_) => Err(parser.error()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_escapes() {
+        assert_eq!(
+            parse(r#""a\"b\\c\/d\n\t\u00e9""#),
+            Ok(Json::String("a\"b\\c/d\n\té".to_owned()))
+        );
+    }
+
+    #[test]
+    fn joins_surrogate_pairs() {
+        assert_eq!(
+            parse(r#""\uD83D\uDE00 \ud83c\uddef""#),
+            Ok(Json::String("😀 \u{1F1EF}".to_owned()))
+        );
+        assert!(parse(r#""\uD83D""#).is_err());
+        assert!(parse(r#""\uD83Dx""#).is_err());
+        assert!(parse(r#""\uDE00""#).is_err());
+        assert!(parse(r#""\uD83D\u0041""#).is_err());
+    }
+
+    #[test]
+    fn reads_nested_values() {
+        let value = parse(r#" {"a": [1, {"b": null}, [true, false]], "c": {}} "#).unwrap();
+        assert_eq!(
+            value,
+            Json::object([
+                (
+                    "a",
+                    Json::Array(vec![
+                        Json::Number(1.0),
+                        Json::object([("b", Json::Null)]),
+                        Json::Array(vec![Json::Bool(true), Json::Bool(false)]),
+                    ])
+                ),
+                ("c", Json::Object(Vec::new())),
+            ])
+        );
+        assert_eq!(value.get("c"), Some(&Json::Object(Vec::new())));
+    }
+
+    #[test]
+    fn reads_numbers() {
+        assert_eq!(parse("42"), Ok(Json::Number(42.0)));
+        assert_eq!(parse("-0.5"), Ok(Json::Number(-0.5)));
+        assert_eq!(parse("1e3"), Ok(Json::Number(1000.0)));
+        assert_eq!(parse("2.5E-1"), Ok(Json::Number(0.25)));
+        assert_eq!(parse("7").unwrap().as_usize(), Some(7));
+        assert_eq!(parse("-7").unwrap().as_usize(), None);
+        assert_eq!(parse("7.5").unwrap().as_usize(), None);
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        for input in [
+            "",
+            "{",
+            "[1,]",
+            "[1 2]",
+            r#"{"a" 1}"#,
+            r#"{"a": 1,}"#,
+            r#"{a: 1}"#,
+            r#""open"#,
+            r#""\x""#,
+            r#""\u12""#,
+            "-",
+            "1e",
+            "tru",
+            "nul",
+            "1 2",
+            "[1] x",
+        ] {
+            assert!(parse(input).is_err(), "{:?} should not parse", input);
+        }
+        assert!(parse(&"[".repeat(60_000)).is_err());
+        let deepest = format!("{}{}", "[".repeat(MAX_DEPTH), "]".repeat(MAX_DEPTH));
+        assert!(parse(&deepest).is_ok());
+        let deeper = format!("{}{}", "[".repeat(MAX_DEPTH + 1), "]".repeat(MAX_DEPTH + 1));
+        assert!(parse(&deeper).is_err());
+    }
+
+    #[test]
+    fn writes_what_it_reads() {
+        let value = Json::object([
+            ("text", Json::from("line\n\"quoted\" \u{1} é 😀")),
+            ("list", Json::array([1, 2])),
+        ]);
+        assert_eq!(parse(&value.to_string()), Ok(value));
+    }
+}
@@ -0,0 +1,254 @@
+mod agent;
+mod board;
+mod cli;
+mod command;
+mod common;
+mod cure_plan;
+mod deck;
+pub mod env;
+mod epidemic_timing;
+#[cfg(feature = "capi")]
+mod ffi;
+mod game;
+mod help;
+mod http;
+mod i18n;
+mod infection_card;
+mod infection_risk;
+mod json;
+mod lobby;
+mod log;
+mod menu;
+mod net;
+mod player;
+mod player_card;
+#[cfg(feature = "python")]
+mod python;
+mod random;
+mod role;
+mod route;
+mod save;
+mod setup;
+mod spectate;
+mod status;
+mod theme;
+mod tui;
+
+pub use crate::setup::Difficulty;
+
+use crossterm::style::{Color, Stylize};
+
+use crate::{
+    cli::Mode,
+    game::Outcome,
+    i18n::{tr, Locale},
+    log::Entry,
+    menu::{menu_injectable, menu_with_commands, MenuInput},
+    player_card::PlayerCard,
+    theme::{Theme, Themed},
+};
+
+/// Brings the spectators up to date, with the outcome once the game is over.
+fn spectate(
+    feed: &mut Option<spectate::Feed>,
+    board: &board::Board,
+    player: &player::Player,
+    others: &[player::Player],
+    outcome: Option<Outcome>,
+) {
+    if let Some(feed) = feed {
+        let mut players = vec![player];
+        players.extend(others);
+        feed.update(board, &players);
+        if let Some(outcome) = outcome {
+            feed.announce(&outcome.to_string());
+        }
+    }
+}
+
+/// Runs the program with the arguments it was started with.
+pub fn run() {
+    Theme::detect().apply();
+    Locale::detect().apply();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let options = match cli::parse(&args) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!(
+                "{}: {}",
+                tr!("error").with(Color::Red).bold().themed(),
+                message
+            );
+            eprintln!("{}", tr!("cli.try_help"));
+            std::process::exit(2);
+        }
+    };
+    if options.help {
+        cli::print_help();
+        return;
+    }
+    if let Some(seed) = options.seed {
+        random::seed(seed);
+    }
+
+    let served = match &options.mode {
+        Mode::Local => None,
+        Mode::Serve(addr) => Some(net::serve(addr, &options)),
+        Mode::Join(addr) => Some(net::join(addr)),
+        Mode::Watch(addr) => Some(spectate::watch(addr)),
+        Mode::Agent => {
+            Theme::Plain.apply();
+            Some(agent::run(&options))
+        }
+        Mode::Http => {
+            Theme::Plain.apply();
+            Some(http::serve(options.port.unwrap_or(http::DEFAULT_PORT)))
+        }
+        Mode::Tui => Some(tui::play(&options)),
+    };
+    if let Some(result) = served {
+        if let Err(error) = result {
+            eprintln!(
+                "{}: {}",
+                tr!("error").with(Color::Red).bold().themed(),
+                error
+            );
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let mut feed = match options.spectate.as_deref().map(spectate::Feed::open) {
+        Some(Err(error)) => {
+            eprintln!(
+                "{}: {}",
+                tr!("error").with(Color::Red).bold().themed(),
+                error
+            );
+            std::process::exit(1);
+        }
+        opened => opened.map(Result::unwrap),
+    };
+
+    let setup = setup::prompt_setup(&options);
+    let (mut board, mut players) = game::start(&setup);
+
+    let mut turn_idx = 0_usize;
+    loop {
+        let mut player = players.remove(turn_idx);
+        if let Some(recap) = board.log.recap(player.plain_name()) {
+            println!("{}", recap);
+        }
+        board
+            .log
+            .record(Entry::Turn(player.plain_name().to_owned()));
+        spectate(&mut feed, &board, &player, &players, None);
+        let mut action = 0;
+        while action < player::ACTIONS_PER_TURN {
+            println!(
+                "{}\n{}",
+                player,
+                tr!("turn.take_turn", action, player::ACTIONS_PER_TURN)
+            );
+            println!(
+                "{}",
+                tr!(
+                    "turn.epidemic_chance",
+                    format!("{:.1}", board.epidemic_chance() * 100.0),
+                    board.epidemics_remaining()
+                )
+            );
+            let actions = player.actions();
+            let input = menu_with_commands(
+                &tr!("menu.actions", player.name()),
+                &tr!("action.do_nothing"),
+                &actions,
+            );
+
+            action += match input {
+                MenuInput::Selection(selection) => player.act(
+                    &mut board,
+                    &mut players,
+                    selection,
+                    player::ACTIONS_PER_TURN - action,
+                ),
+                MenuInput::Command(text) => match command::parse(&text) {
+                    Ok(command) => player.perform(
+                        &mut board,
+                        &mut players,
+                        &command,
+                        player::ACTIONS_PER_TURN - action,
+                    ),
+                    Err(error) => {
+                        println!(
+                            "{}: {}",
+                            tr!("error").with(Color::Red).bold().themed(),
+                            error
+                        );
+                        0
+                    }
+                },
+            };
+            if board.all_cured() {
+                spectate(&mut feed, &board, &player, &players, Some(Outcome::Victory));
+                println!("{}", Outcome::Victory);
+                return;
+            }
+            spectate(&mut feed, &board, &player, &players, None);
+        }
+
+        let mut event_cards = Vec::new();
+
+        for card in player.hand.clone() {
+            if let PlayerCard::EventCard(event) = card {
+                event_cards.push(event)
+            }
+        }
+
+        while !event_cards.is_empty() {
+            let selection = menu_injectable(
+                &tr!(
+                    "menu.events_before_draw",
+                    player.name(),
+                    player.hand.len(),
+                    player::MAX_CARDS_IN_HAND
+                ),
+                &tr!("action.draw_cards"),
+                &event_cards,
+            );
+
+            if selection == 0 {
+                break;
+            }
+            let selection = selection - 1;
+            let idx = player
+                .hand
+                .iter()
+                .position(|x| *x == PlayerCard::EventCard(event_cards[selection]))
+                .unwrap();
+            player.hand.remove(idx);
+            let event = event_cards.remove(selection);
+            player.play_event(&mut board, event);
+        }
+
+        if let Err(outcome) = game::draw_cards(&mut board, &mut player) {
+            spectate(&mut feed, &board, &player, &players, Some(outcome));
+            println!("{}", outcome);
+            return;
+        }
+
+        player.enforce_hand_limit(&mut board);
+
+        if let Err(outcome) = game::infect_cities(&mut board) {
+            spectate(&mut feed, &board, &player, &players, Some(outcome));
+            println!("{}", outcome);
+            return;
+        }
+
+        spectate(&mut feed, &board, &player, &players, None);
+        players.insert(turn_idx, player);
+        turn_idx += 1;
+        turn_idx %= players.len();
+    }
+}
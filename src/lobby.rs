@@ -0,0 +1,277 @@
+use crate::{
+    command::{is_verb, normalize},
+    game::{Phase, Seeded},
+    i18n::tr,
+    player::{self, Player},
+    role::Role,
+    save::{Loaded, Save},
+    setup::{Difficulty, Setup, MAX_PLAYERS, MIN_PLAYERS},
+    status::Status,
+};
+
+/// What can be typed in the lobby of a server, outside of a game.
+#[derive(Debug, PartialEq)]
+pub enum LobbyCommand {
+    List,
+    Create(usize, Difficulty),
+    /// A game by its number and the role to play in it, which may be left out when the
+    /// host has already named the players.
+    Sit(usize, String),
+    Rejoin(usize, String),
+    Start,
+    Leave,
+}
+
+fn game_number(input: &str) -> Result<usize, String> {
+    input.parse().map_err(|_| tr!("lobby.expected_game", input))
+}
+
+pub fn parse(input: &str) -> Result<LobbyCommand, String> {
+    let input = input.trim();
+    let (verb, rest) = match input.split_once(char::is_whitespace) {
+        Some((verb, rest)) => (verb, rest.trim()),
+        None => (input, ""),
+    };
+    let word = normalize(verb);
+    let (first, rest) = match rest.split_once(char::is_whitespace) {
+        Some((first, rest)) => (first, rest.trim()),
+        None => (rest, ""),
+    };
+    if is_verb(&word, "lobby.verb.list") {
+        Ok(LobbyCommand::List)
+    } else if is_verb(&word, "lobby.verb.create") {
+        let players = first
+            .parse()
+            .ok()
+            .filter(|count| (MIN_PLAYERS..=MAX_PLAYERS).contains(count))
+            .ok_or_else(|| tr!("setup.player_count_error", MIN_PLAYERS, MAX_PLAYERS))?;
+        let difficulty = if rest.is_empty() {
+            Difficulty::Standard
+        } else {
+            Difficulty::parse(rest)?
+        };
+        Ok(LobbyCommand::Create(players, difficulty))
+    } else if is_verb(&word, "lobby.verb.sit") {
+        Ok(LobbyCommand::Sit(game_number(first)?, rest.to_owned()))
+    } else if is_verb(&word, "lobby.verb.rejoin") {
+        Ok(LobbyCommand::Rejoin(game_number(first)?, rest.to_owned()))
+    } else if is_verb(&word, "lobby.verb.start") {
+        Ok(LobbyCommand::Start)
+    } else if is_verb(&word, "lobby.verb.leave") {
+        Ok(LobbyCommand::Leave)
+    } else {
+        Err(tr!("command.unknown", verb, tr!("lobby.usage")))
+    }
+}
+
+/// One game on a server, from the first player sitting down until it is over.
+pub struct Table {
+    pub(crate) size: usize,
+    pub(crate) difficulty: Difficulty,
+    pub(crate) seed: u64,
+    /// The players in seat order and their roles, filled in as they sit down.
+    pub(crate) players: Vec<(String, Role)>,
+    /// Whether the host named the players, so the seats are kept for them.
+    pub(crate) named: bool,
+    /// The connection playing each seat.
+    pub(crate) seats: Vec<Option<usize>>,
+    /// What each player gives to get back in, once they have sat down.
+    pub(crate) tokens: Vec<Option<String>>,
+    /// `None` until the players start it.
+    pub(crate) game: Option<Seeded>,
+    /// How many log entries have already been sent out.
+    pub(crate) logged: usize,
+    pub(crate) save: Option<Save>,
+    /// Who created the game in the lobby, until somebody sits down at it.
+    pub(crate) host: Option<usize>,
+    /// Set for a game picked up from its save until every player is back in, so nobody
+    /// plays on without the others.
+    pub(crate) regathering: bool,
+}
+
+impl Table {
+    /// A game created in the lobby, with its seats free for anyone.
+    pub fn open(size: usize, difficulty: Difficulty, seed: u64) -> Self {
+        Self {
+            size,
+            difficulty,
+            seed,
+            players: Vec::new(),
+            named: false,
+            seats: Vec::new(),
+            tokens: Vec::new(),
+            game: None,
+            logged: 0,
+            save: None,
+            host: None,
+            regathering: false,
+        }
+    }
+
+    /// A game the host set up, waiting for the players it names.
+    pub fn named(setup: Setup, seed: u64) -> Self {
+        let size = setup.players.len();
+        Self {
+            players: setup.players,
+            named: true,
+            seats: vec![None; size],
+            tokens: vec![None; size],
+            ..Self::open(size, setup.difficulty, seed)
+        }
+    }
+
+    /// A game picked up again from its save.
+    pub fn resumed(loaded: Loaded, save: Save) -> Self {
+        let game = &loaded.seeded.game;
+        let players = game
+            .players
+            .iter()
+            .map(|player| (player.plain_name().to_owned(), player.role()))
+            .collect();
+        Self {
+            size: game.players.len(),
+            difficulty: loaded.difficulty,
+            seed: loaded.seeded.seed,
+            players,
+            named: true,
+            seats: vec![None; game.players.len()],
+            tokens: loaded.tokens,
+            logged: game.board.log.entries().len(),
+            game: Some(loaded.seeded),
+            save: Some(save),
+            host: None,
+            regathering: true,
+        }
+    }
+
+    /// Whether nobody has sat down at a game made in the lobby since it was created or since
+    /// the last of its players left.
+    pub fn empty(&self) -> bool {
+        self.game.is_none() && !self.named && self.players.is_empty()
+    }
+
+    pub fn seat(&self, conn: usize) -> Option<usize> {
+        self.seats.iter().position(|seat| *seat == Some(conn))
+    }
+
+    /// Whether every seat has a player in it.
+    pub fn full(&self) -> bool {
+        self.players.len() == self.size && self.seats.iter().all(Option::is_some)
+    }
+
+    pub fn free_roles(&self) -> Vec<&'static str> {
+        Role::ALL
+            .iter()
+            .filter(|role| !self.players.iter().any(|(_, taken)| taken == *role))
+            .map(|role| role.name())
+            .collect()
+    }
+
+    /// Who the game is still waiting for before it can start.
+    pub fn waiting(&self) -> String {
+        if self.full() {
+            tr!("lobby.ready")
+        } else if self.named {
+            let free: Vec<&str> = self
+                .players
+                .iter()
+                .zip(&self.seats)
+                .filter(|(_, seat)| seat.is_none())
+                .map(|((name, _), _)| name.as_str())
+                .collect();
+            tr!("net.waiting_players", free.join(", "))
+        } else {
+            tr!("lobby.waiting_seats", self.size - self.players.len())
+        }
+    }
+
+    /// The game as listed in the lobby.
+    pub fn describe(&self, id: usize) -> String {
+        let players: Vec<String> = self
+            .players
+            .iter()
+            .zip(&self.seats)
+            .map(|((name, role), seat)| match seat {
+                Some(_) => tr!("lobby.player", name, role.name()),
+                None => tr!("lobby.player_away", name, role.name()),
+            })
+            .collect();
+        let key = if self.game.is_some() {
+            "lobby.game_playing"
+        } else {
+            "lobby.game_open"
+        };
+        tr!(
+            key,
+            id,
+            self.difficulty,
+            self.players.len(),
+            self.size,
+            players.join(", ")
+        )
+    }
+
+    /// The status screen as the player in `seat` sees it.
+    pub fn status(&self, seat: usize) -> String {
+        let Some(Seeded { game, .. }) = &self.game else {
+            return self.waiting();
+        };
+        let others: Vec<Player> = game
+            .players
+            .iter()
+            .enumerate()
+            .filter(|(other, _)| *other != seat)
+            .map(|(_, player)| player.clone())
+            .collect();
+        Status {
+            board: &game.board,
+            player: &game.players[seat],
+            others: &others,
+        }
+        .to_string()
+    }
+
+    /// What the player in `seat` is asked to do next, with their hand when it matters.
+    pub fn view(&self, seat: usize) -> String {
+        let Some(Seeded { game, .. }) = &self.game else {
+            return self.waiting();
+        };
+        let waiting = |other: usize| tr!("game.waiting_for", game.players[other].name());
+        match game.phase {
+            Phase::Over(outcome) => outcome.to_string(),
+            Phase::Actions if seat == game.current => format!(
+                "{}\n{}",
+                game.players[seat],
+                tr!(
+                    "net.your_turn",
+                    player::ACTIONS_PER_TURN - game.actions_left,
+                    player::ACTIONS_PER_TURN
+                )
+            ),
+            Phase::Offer { to, card, giving } if seat == to => {
+                let key = if giving {
+                    "net.offer_give"
+                } else {
+                    "net.offer_take"
+                };
+                tr!(key, game.players[game.current].name(), card)
+            }
+            Phase::Discard { player, .. } if seat == player => format!(
+                "{}\n{}",
+                game.players[seat],
+                tr!(
+                    "net.discard",
+                    game.players[seat].hand.len(),
+                    player::MAX_CARDS_IN_HAND
+                )
+            ),
+            _ => {
+                let other = game.waiting_on().unwrap_or(game.current);
+                match self.seats[other] {
+                    Some(_) => waiting(other),
+                    None => tr!("net.away", game.players[other].name()),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,281 @@
+use std::{
+    cmp::Reverse,
+    collections::BTreeMap,
+    io::{stdout, Write},
+};
+
+use crossterm::{
+    cursor::MoveTo,
+    event::{read, Event as TerminalEvent, KeyCode, KeyEventKind},
+    queue,
+    style::{Print, Stylize},
+    terminal::{size, Clear, ClearType},
+};
+
+use crate::{
+    board::Cities,
+    common::Color,
+    i18n::tr,
+    menu::{interactive, strip_ansi},
+    player_card::{Events, PlayerCard},
+    theme::Themed,
+    tui::FullScreen,
+};
+
+/// What a planning note is pinned to.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Pin {
+    City(Cities),
+    Player(String),
+}
+
+impl std::fmt::Display for Pin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::City(city) => write!(f, "{}", city),
+            Self::Player(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// Something that happened at the table, kept so it does not scroll away.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Entry {
+    Turn(String),
+    Moved(String, Cities),
+    Built(String, Cities),
+    Treated(String, Cities, Color),
+    Shared(String, String, Cities),
+    Cured(String, Color),
+    Eradicated(Color),
+    EventPlayed(String, Events),
+    Drew(String, PlayerCard),
+    Discarded(String, PlayerCard),
+    Epidemic(Cities),
+    Infected(Cities, Color),
+    Outbreak(Cities, Color),
+    /// A player talking to the table.
+    Chat(String, String),
+    /// A player pinning a note, or taking the note off when it is empty.
+    Note(String, Pin, String),
+}
+
+/// City cards are named by their city alone, without the country and population.
+fn card_name(card: &PlayerCard) -> String {
+    match card {
+        PlayerCard::CityCard(city) => city.city.to_string(),
+        _ => card.to_string(),
+    }
+}
+
+impl std::fmt::Display for Entry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            Self::Turn(player) => tr!("log.turn", player),
+            Self::Moved(player, to) => tr!("log.moved", player, to),
+            Self::Built(player, city) => tr!("log.built", player, city),
+            Self::Treated(player, city, color) => tr!("log.treated", player, color, city),
+            Self::Shared(from, to, card) => tr!("log.shared", from, card, to),
+            Self::Cured(player, color) => tr!("log.cured", player, color),
+            Self::Eradicated(color) => tr!("log.eradicated", color),
+            Self::EventPlayed(player, event) => tr!("log.event", player, event),
+            Self::Drew(player, card) => tr!("log.drew", player, card_name(card)),
+            Self::Discarded(player, card) => tr!("log.discarded", player, card_name(card)),
+            Self::Epidemic(city) => tr!("log.epidemic", city),
+            Self::Infected(city, color) => tr!("log.infected", city, color),
+            Self::Outbreak(city, color) => tr!("log.outbreak", city.name(), color),
+            Self::Chat(player, text) => tr!("log.chat", player, text),
+            Self::Note(player, pin, text) if text.is_empty() => tr!("log.unpinned", player, pin),
+            Self::Note(player, pin, text) => tr!("log.note", player, pin, text),
+        };
+        write!(f, "{}", text)
+    }
+}
+
+/// Everything that has happened this game, oldest first.
+#[derive(Debug, Default)]
+pub struct GameLog {
+    entries: Vec<Entry>,
+}
+
+impl GameLog {
+    pub fn record(&mut self, entry: Entry) {
+        self.entries.push(entry);
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Entries since `player` last started a turn, or since the game began for their first turn.
+    fn since_last_turn(&self, player: &str) -> (&[Entry], bool) {
+        match self
+            .entries
+            .iter()
+            .rposition(|entry| *entry == Entry::Turn(player.to_owned()))
+        {
+            Some(idx) => (&self.entries[idx + 1..], true),
+            None => (&self.entries, false),
+        }
+    }
+
+    /// One line on what changed since `player`'s last turn: outbreaks, epidemics, cures and the
+    /// cities that gained cubes, most first. `None` when nothing worth mentioning happened.
+    pub fn recap(&self, player: &str) -> Option<String> {
+        let (entries, played_before) = self.since_last_turn(player);
+        let mut outbreaks = 0;
+        let mut parts = Vec::new();
+        let mut cubes: BTreeMap<Cities, usize> = BTreeMap::new();
+        for entry in entries {
+            match entry {
+                Entry::Outbreak(..) => outbreaks += 1,
+                Entry::Epidemic(city) => parts.push(tr!("log.recap.epidemic", city)),
+                Entry::Cured(_, color) => parts.push(tr!("log.recap.cured", color)),
+                Entry::Eradicated(color) => parts.push(tr!("log.recap.eradicated", color)),
+                Entry::Infected(city, _) => *cubes.entry(*city).or_default() += 1,
+                _ => {}
+            }
+        }
+        match outbreaks {
+            0 => {}
+            1 => parts.insert(0, tr!("log.recap.outbreak")),
+            _ => parts.insert(0, tr!("log.recap.outbreaks", outbreaks)),
+        }
+        let mut cubes: Vec<(Cities, usize)> = cubes.into_iter().collect();
+        cubes.sort_by_key(|(_, added)| Reverse(*added));
+        parts.extend(
+            cubes
+                .into_iter()
+                .map(|(city, added)| tr!("log.recap.cubes", city, added)),
+        );
+        if parts.is_empty() {
+            return None;
+        }
+        let key = if played_before {
+            "log.recap"
+        } else {
+            "log.recap.first"
+        };
+        Some(tr!(key, parts.join(", ")))
+    }
+
+    /// The last `count` things said at the table, oldest first, with who said them.
+    pub fn chat(&self, count: usize) -> Vec<(&str, &str)> {
+        let said: Vec<(&str, &str)> = self
+            .entries
+            .iter()
+            .filter_map(|entry| match entry {
+                Entry::Chat(player, text) => Some((player.as_str(), text.as_str())),
+                _ => None,
+            })
+            .collect();
+        said[said.len().saturating_sub(count)..].to_vec()
+    }
+
+    /// The notes pinned now, the latest on each city or player, with who pinned them.
+    pub fn notes(&self) -> Vec<(&Pin, &str, &str)> {
+        let mut notes: Vec<(&Pin, &str, &str)> = Vec::new();
+        for entry in &self.entries {
+            if let Entry::Note(player, pin, text) = entry {
+                notes.retain(|(other, _, _)| *other != pin);
+                if !text.is_empty() {
+                    notes.push((pin, player, text));
+                }
+            }
+        }
+        notes
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        self.entries
+            .iter()
+            .map(|entry| match entry {
+                Entry::Turn(_) => format!("{}", entry.to_string().bold().themed()),
+                _ => format!("  {}", entry),
+            })
+            .collect()
+    }
+}
+
+/// Shows the whole log, scrollable with the arrow keys on a terminal or printed in full when
+/// piped.
+pub fn show_log(log: &GameLog) {
+    let lines = log.lines();
+    if lines.is_empty() {
+        println!("{}", tr!("log.empty"));
+        return;
+    }
+    if !interactive() || page(&lines).is_err() {
+        println!("{:=^94}", format!(" {} ", tr!("log.title")));
+        for line in lines {
+            println!("{}", line);
+        }
+    }
+}
+
+/// A full-screen pager that opens on the newest entries.
+fn page(lines: &[String]) -> std::io::Result<()> {
+    let mut out = stdout();
+    let _screen = FullScreen::enter()?;
+    let mut top = usize::MAX;
+    loop {
+        let (width, height) = size()?;
+        let rows = (height as usize).saturating_sub(2).max(1);
+        let last = lines.len().saturating_sub(rows);
+        top = top.min(last);
+        queue!(out, Clear(ClearType::All), MoveTo(0, 0))?;
+        let title = format!(" {} ", tr!("log.title"));
+        queue!(out, Print(format!("{:=^1$}", title, width as usize)))?;
+        for (row, line) in lines.iter().skip(top).take(rows).enumerate() {
+            // Long lines lose their colors rather than wrap onto the next row.
+            let plain = strip_ansi(line);
+            let shown = if plain.chars().count() > width as usize {
+                plain.chars().take(width as usize).collect()
+            } else {
+                line.clone()
+            };
+            queue!(out, MoveTo(0, row as u16 + 1), Print(shown))?;
+        }
+        let hint = tr!(
+            "log.hint",
+            top + 1,
+            (top + rows).min(lines.len()),
+            lines.len()
+        );
+        queue!(out, MoveTo(0, height.saturating_sub(1)), Print(hint))?;
+        out.flush()?;
+
+        if let TerminalEvent::Key(key) = read()? {
+            if key.kind == KeyEventKind::Release {
+                continue;
+            }
+            match key.code {
+                KeyCode::Up => top = top.saturating_sub(1),
+                KeyCode::Down => top = (top + 1).min(last),
+                KeyCode::PageUp => top = top.saturating_sub(rows),
+                KeyCode::PageDown => top = (top + rows).min(last),
+                KeyCode::Home => top = 0,
+                KeyCode::End => top = last,
+                KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => return Ok(()),
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn an_outbreak_names_its_color_once() {
+        let entry = Entry::Outbreak(Cities::Baghdad, Color::Black);
+        assert_eq!(
+            strip_ansi(&entry.to_string()),
+            "Outbreak in Baghdad (Black)"
+        );
+        // Outbreaks can spread a color other than the city's own.
+        let entry = Entry::Outbreak(Cities::Paris, Color::Black);
+        assert_eq!(strip_ansi(&entry.to_string()), "Outbreak in Paris (Black)");
+    }
+}
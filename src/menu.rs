@@ -1,52 +1,382 @@
-use std::{fmt::Display, io::Write};
+use std::{
+    fmt::Display,
+    io::{stdin, stdout, IsTerminal, Write},
+};
 
-use crossterm::style::{Color, Stylize};
+use crossterm::{
+    cursor::{position, Hide, MoveToPreviousLine, Show},
+    event::{read, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    queue,
+    style::{Attribute, Color, Print, SetAttribute, Stylize},
+    terminal::{disable_raw_mode, enable_raw_mode, size, Clear, ClearType},
+};
+
+use crate::{
+    command::{self, normalize},
+    i18n::tr,
+    theme::Themed,
+};
+
+/// Most options the arrow-key selector shows at once.
+const PAGE_SIZE: usize = 12;
+
+/// The arrow-key selector needs a terminal on both ends; piped games fall back to typed numbers.
+pub fn interactive() -> bool {
+    stdin().is_terminal() && stdout().is_terminal()
+}
+
+/// Drops the color codes from an option so it can be filtered and measured.
+pub fn strip_ansi(text: &str) -> String {
+    let mut plain = String::new();
+    let mut chars = text.chars();
+    while let Some(ch) = chars.next() {
+        if ch == '\x1b' {
+            for ch in chars.by_ref() {
+                if ch.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            plain.push(ch);
+        }
+    }
+    plain
+}
+
+/// Columns `ch` takes in a terminal: emoji and East Asian wide characters take two, each half
+/// of a flag one, and joiners, variation selectors and combining accents none.
+fn char_width(ch: char) -> usize {
+    match ch as u32 {
+        0x0300..=0x036F | 0x200B..=0x200D | 0xFE00..=0xFE0F => 0,
+        0x1F1E6..=0x1F1FF => 1,
+        0x1100..=0x115F
+        | 0x2E80..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x1F000..=0x1FAFF
+        | 0x20000..=0x3FFFD => 2,
+        _ => 1,
+    }
+}
+
+/// Columns `text` takes once its color codes are dropped. A symbol followed by the emoji
+/// variation selector, like ◼️, is drawn as an emoji two columns wide.
+pub fn display_width(text: &str) -> usize {
+    let plain = strip_ansi(text);
+    let mut width = 0;
+    let mut chars = plain.chars().peekable();
+    while let Some(ch) = chars.next() {
+        width += match chars.peek() {
+            Some('\u{FE0F}') => 2,
+            _ => char_width(ch),
+        };
+    }
+    width
+}
+
+/// The longest start of `text`, without its color codes, that fits in `width` columns.
+pub fn truncate_to_width(text: &str, width: usize) -> String {
+    let plain = strip_ansi(text);
+    let mut kept = String::new();
+    let mut chars = plain.chars().peekable();
+    let mut used = 0;
+    while let Some(ch) = chars.next() {
+        let (cell, selector) = match chars.peek() {
+            Some(&'\u{FE0F}') => (2, chars.next()),
+            _ => (char_width(ch), None),
+        };
+        if used + cell > width {
+            break;
+        }
+        used += cell;
+        kept.push(ch);
+        kept.extend(selector);
+    }
+    kept
+}
+
+/// One row of the selector: the number typed at the old prompt, the colored label and a plain
+/// copy for filtering.
+struct Entry {
+    value: usize,
+    label: String,
+    plain: String,
+}
+
+/// Raw-mode selector: arrows move, typing filters, PgUp/PgDn page and Enter picks. Esc picks
+/// the first option when there is one. Returns the same number the typed menus would, or with
+/// `commands`, the typed text when Enter is pressed and no option matches it.
+fn select(
+    title: &str,
+    first_option: Option<&str>,
+    options: &[impl Display],
+    commands: bool,
+) -> std::io::Result<MenuInput> {
+    let mut entries = Vec::new();
+    if let Some(first_option) = first_option {
+        entries.push(Entry {
+            value: 0,
+            label: format!("0. {}", first_option),
+            plain: format!("0. {}", strip_ansi(first_option)),
+        });
+    }
+    for (i, option) in options.iter().enumerate() {
+        let label = format!("{}. {}", i + 1, option);
+        entries.push(Entry {
+            value: i + 1,
+            plain: strip_ansi(&label),
+            label,
+        });
+    }
+
+    let mut out = stdout();
+    enable_raw_mode()?;
+    queue!(out, Hide)?;
+    if position()?.0 != 0 {
+        queue!(out, Print("\r\n"))?;
+    }
+    let mut drawn = 0;
+    let result: std::io::Result<Result<&Entry, String>> = (|| {
+        let mut filter = String::new();
+        let mut cursor = 0;
+        loop {
+            let query = normalize(&filter);
+            let visible: Vec<&Entry> = entries
+                .iter()
+                .filter(|entry| normalize(&entry.plain).contains(&query))
+                .collect();
+            cursor = cursor.min(visible.len().saturating_sub(1));
+
+            let (width, height) = size()?;
+            let page_size = PAGE_SIZE.min((height as usize).saturating_sub(4)).max(1);
+            let pages = visible.len().div_ceil(page_size).max(1);
+            let page = cursor / page_size;
+
+            if drawn > 0 {
+                queue!(out, MoveToPreviousLine(drawn))?;
+            }
+            queue!(out, Clear(ClearType::FromCursorDown))?;
+            // Every line is kept narrower than the terminal so the redraw can count on it.
+            let room = (width as usize).saturating_sub(1);
+            let mut lines = vec![format!("{:=^94}", format!(" {} ", title))];
+            for (i, entry) in visible
+                .iter()
+                .enumerate()
+                .skip(page * page_size)
+                .take(page_size)
+            {
+                if i == cursor {
+                    lines.push(format!(
+                        "{}  > {}{}",
+                        SetAttribute(Attribute::Reverse),
+                        truncate_to_width(&entry.plain, room.saturating_sub(4)),
+                        SetAttribute(Attribute::Reset)
+                    ));
+                } else {
+                    lines.push(format!("    {}", entry.label));
+                }
+            }
+            if visible.is_empty() {
+                lines.push(format!("    {}", tr!("menu.no_match", filter)));
+            }
+            lines.push(format!(
+                "{}",
+                tr!(
+                    if commands {
+                        "menu.selector_hint_commands"
+                    } else if first_option.is_some() {
+                        "menu.selector_hint_back"
+                    } else {
+                        "menu.selector_hint"
+                    },
+                    filter,
+                    page + 1,
+                    pages
+                )
+                .with(Color::DarkGrey)
+                .themed()
+            ));
+            for line in &lines {
+                if display_width(line) > room {
+                    queue!(out, Print(truncate_to_width(line, room)), Print("\r\n"))?;
+                } else {
+                    queue!(out, Print(line), Print("\r\n"))?;
+                }
+            }
+            drawn = lines.len() as u16;
+            out.flush()?;
+
+            let Event::Key(KeyEvent {
+                code,
+                modifiers,
+                kind,
+                ..
+            }) = read()?
+            else {
+                continue;
+            };
+            if kind == KeyEventKind::Release {
+                continue;
+            }
+            match code {
+                KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                    queue!(out, Show)?;
+                    out.flush()?;
+                    disable_raw_mode()?;
+                    std::process::exit(130);
+                }
+                KeyCode::Up => cursor = (cursor + visible.len().max(1) - 1) % visible.len().max(1),
+                KeyCode::Down => cursor = (cursor + 1) % visible.len().max(1),
+                KeyCode::PageUp => cursor = cursor.saturating_sub(page_size),
+                KeyCode::PageDown => cursor += page_size,
+                KeyCode::Home => cursor = 0,
+                KeyCode::End => cursor = visible.len().saturating_sub(1),
+                // A whole command typed out wins over the option it happens to filter down to.
+                KeyCode::Enter if commands && command::parse(&filter).is_ok() => {
+                    return Ok(Err(filter.trim().to_owned()))
+                }
+                KeyCode::Enter if !visible.is_empty() => return Ok(Ok(visible[cursor])),
+                KeyCode::Enter if commands && !filter.trim().is_empty() => {
+                    return Ok(Err(filter.trim().to_owned()))
+                }
+                KeyCode::Esc if !filter.is_empty() => filter.clear(),
+                // Esc would pass an action in the command menu, so there it only clears.
+                KeyCode::Esc if first_option.is_some() && !commands => return Ok(Ok(&entries[0])),
+                KeyCode::Backspace => {
+                    filter.pop();
+                }
+                KeyCode::Char(ch)
+                    if !modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+                {
+                    filter.push(ch);
+                    cursor = 0;
+                }
+                _ => {}
+            }
+        }
+    })();
+    if drawn > 0 {
+        queue!(out, MoveToPreviousLine(drawn))?;
+    }
+    queue!(out, Clear(ClearType::FromCursorDown), Show)?;
+    out.flush()?;
+    disable_raw_mode()?;
+
+    println!("{:=^94}", format!(" {} ", title));
+    match result? {
+        Ok(entry) => {
+            println!("\t{}", entry.label);
+            Ok(MenuInput::Selection(entry.value))
+        }
+        Err(command) => {
+            println!("\t> {}", command);
+            Ok(MenuInput::Command(command))
+        }
+    }
+}
 
 pub fn menu_cancelable(title: &str, options: &[impl Display]) -> usize {
-    menu_injectable(title, "Go Back to Previous Menu", options)
+    menu_injectable(title, &tr!("menu.go_back"), options)
 }
 
 pub fn menu_injectable(title: &str, first_option: &str, options: &[impl Display]) -> usize {
+    if interactive() {
+        if let Ok(MenuInput::Selection(selection)) =
+            select(title, Some(first_option), options, false)
+        {
+            return selection;
+        }
+    }
+    match prompt_injectable(title, first_option, options, false) {
+        MenuInput::Selection(selection) => selection,
+        // Only handed back when commands are asked for.
+        MenuInput::Command(_) => 0,
+    }
+}
+
+/// What was entered at a menu that also accepts typed commands.
+pub enum MenuInput {
+    Selection(usize),
+    Command(String),
+}
+
+pub fn menu_with_commands(title: &str, first_option: &str, options: &[impl Display]) -> MenuInput {
+    if interactive() {
+        if let Ok(input) = select(title, Some(first_option), options, true) {
+            return input;
+        }
+    }
+    prompt_injectable(title, first_option, options, true)
+}
+
+/// The typed menu behind `menu_injectable`, which with `commands` also hands back any input
+/// that is not a number.
+fn prompt_injectable(
+    title: &str,
+    first_option: &str,
+    options: &[impl Display],
+    commands: bool,
+) -> MenuInput {
     loop {
         println!("{:=^94}", format!(" {} ", title));
         println!("\t{}. {}", 0, first_option);
-        for i in 0..options.len() {
-            println!("\t{}. {}", i + 1, options[i]);
+        for (i, option) in options.iter().enumerate() {
+            println!("\t{}. {}", i + 1, option);
         }
-        print!("{}: ", "Enter selection");
+        let prompt = if commands {
+            tr!("menu.enter_selection_or_command")
+        } else {
+            tr!("menu.enter_selection")
+        };
+        print!("{} ", prompt);
         std::io::stdout().flush().unwrap_or_default();
         let mut input = String::new();
         match std::io::stdin().read_line(&mut input) {
             Ok(_) => match input.trim().parse::<usize>() {
                 Ok(value) => {
                     if value <= options.len() {
-                        return value;
+                        return MenuInput::Selection(value);
                     } else {
                         println!(
-                            "{}: Input should be at most {}",
-                            "Error".with(Color::Red).bold(),
-                            options.len()
+                            "{}: {}",
+                            tr!("error").with(Color::Red).bold().themed(),
+                            tr!("menu.at_most", options.len())
                         );
                     }
-                },
+                }
+                Err(_) if commands && !input.trim().is_empty() => {
+                    return MenuInput::Command(input.trim().to_owned())
+                }
                 Err(_) => println!(
-                    "{}: Invalid input: '{}'",
-                    "Error".with(Color::Red).bold(),
-                    input
+                    "{}: {}",
+                    tr!("error").with(Color::Red).bold().themed(),
+                    tr!("menu.invalid_input", input)
                 ),
             },
-            Err(_) => println!("{}: Could not read input.", "Error".with(Color::Red).bold()),
+            Err(_) => println!(
+                "{}: {}",
+                tr!("error").with(Color::Red).bold().themed(),
+                tr!("menu.read_failed")
+            ),
         }
     }
 }
 
 pub fn menu(title: &str, options: &[impl Display]) -> usize {
+    if interactive() {
+        if let Ok(MenuInput::Selection(selection)) = select(title, None, options, false) {
+            return selection;
+        }
+    }
     loop {
         println!("{:=^94}", format!(" {} ", title));
-        for i in 0..options.len() {
-            println!("\t{}. {}", i + 1, options[i]);
+        for (i, option) in options.iter().enumerate() {
+            println!("\t{}. {}", i + 1, option);
         }
-        print!("{}: ", "Enter selection");
+        print!("{} ", tr!("menu.enter_selection"));
         std::io::stdout().flush().unwrap_or_default();
         let mut input = String::new();
         match std::io::stdin().read_line(&mut input) {
@@ -56,19 +386,51 @@ pub fn menu(title: &str, options: &[impl Display]) -> usize {
                         return value;
                     } else {
                         println!(
-                            "{}: Input should be between 1 and {}",
-                            "Error".with(Color::Red).bold(),
-                            options.len()
+                            "{}: {}",
+                            tr!("error").with(Color::Red).bold().themed(),
+                            tr!("menu.between", options.len())
                         );
                     }
-                },
+                }
                 Err(_) => println!(
-                    "{}: Invalid input: '{}'",
-                    "Error".with(Color::Red).bold(),
-                    input
+                    "{}: {}",
+                    tr!("error").with(Color::Red).bold().themed(),
+                    tr!("menu.invalid_input", input)
                 ),
             },
-            Err(_) => println!("{}: Could not read input.", "Error".with(Color::Red).bold()),
+            Err(_) => println!(
+                "{}: {}",
+                tr!("error").with(Color::Red).bold().themed(),
+                tr!("menu.read_failed")
+            ),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn color_codes_take_no_room() {
+        let colored = format!("{}", "Atlanta".with(Color::Blue));
+        assert_eq!(display_width(&colored), 7);
+    }
+
+    #[test]
+    fn emoji_and_flags_take_two_columns() {
+        assert_eq!(display_width("👥 Bo"), 5);
+        assert_eq!(display_width("◼️"), 2);
+        assert_eq!(display_width("🇯🇵 Tokyo"), 8);
+        assert_eq!(display_width("São Paulo"), 9);
+    }
+
+    #[test]
+    fn truncation_stops_before_a_wide_glyph_that_does_not_fit() {
+        assert_eq!(truncate_to_width("ab👤cd", 3), "ab");
+        assert_eq!(truncate_to_width("ab👤cd", 4), "ab👤");
+        assert_eq!(truncate_to_width("x◼️y", 3), "x◼️");
+        let colored = format!("{}", "Lima".with(Color::Yellow));
+        assert_eq!(truncate_to_width(&colored, 10), "Lima");
+    }
+}
@@ -0,0 +1,658 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{Shutdown, TcpListener, TcpStream},
+    sync::mpsc::{self, Sender},
+    thread,
+    time::Duration,
+};
+
+use crossterm::style::{Color as ConsoleColor, Stylize};
+
+use crate::{
+    cli::Options,
+    command::{self, Command},
+    game::{self, Phase, Seeded},
+    i18n::tr,
+    lobby::{self, LobbyCommand, Table},
+    player::Player,
+    role::Role,
+    save::{self, Save},
+    setup::{self, check_name, Difficulty, Setup},
+    spectate::Feed,
+    theme::Themed,
+};
+
+pub const DEFAULT_PORT: u16 = 7777;
+
+/// How long a client may leave the server waiting on a write before it is dropped, so one
+/// stalled connection cannot hold up every game.
+pub(crate) const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long a line from a client may be before the connection is dropped, so nobody can make
+/// the server hold an endless one in memory.
+const MAX_LINE: u64 = 4 * 1024;
+
+/// Adds `port` to an address given without one, e.g. `localhost`.
+pub fn with_port(addr: &str, port: u16) -> String {
+    match addr.rsplit_once(':') {
+        Some((_, given)) if given.parse::<u16>().is_ok() => addr.to_owned(),
+        _ => format!("{}:{}", addr, port),
+    }
+}
+
+/// What the connection threads tell the game thread. Connections are numbered as they arrive.
+enum Message {
+    Connected(usize, TcpStream),
+    Line(usize, String),
+    Disconnected(usize),
+}
+
+/// Accepts connections and starts a thread reading lines from each one.
+fn accept(listener: TcpListener, messages: Sender<Message>) {
+    for (conn, stream) in listener.incoming().enumerate() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        let Ok(reader) = stream.try_clone() else {
+            continue;
+        };
+        if messages.send(Message::Connected(conn, stream)).is_err() {
+            return;
+        }
+        let messages = messages.clone();
+        thread::spawn(move || {
+            let mut reader = BufReader::new(reader);
+            loop {
+                let mut line = String::new();
+                // A line the limit cuts short is dropped along with the connection.
+                match (&mut reader).take(MAX_LINE).read_line(&mut line) {
+                    Ok(0) | Err(_) => break,
+                    Ok(read) if read as u64 == MAX_LINE && !line.ends_with('\n') => break,
+                    Ok(_) => {}
+                }
+                let line = line.trim_end_matches(['\n', '\r']).to_owned();
+                if messages.send(Message::Line(conn, line)).is_err() {
+                    return;
+                }
+            }
+            let _ = messages.send(Message::Disconnected(conn));
+        });
+    }
+}
+
+fn error(message: &str) -> String {
+    format!(
+        "{}: {}",
+        tr!("error").with(ConsoleColor::Red).bold().themed(),
+        message
+    )
+}
+
+/// Someone connected to the server, in the lobby or at one of its games.
+struct Client {
+    stream: TcpStream,
+    /// What they called themselves, which is the first thing asked.
+    name: Option<String>,
+    /// The number of the game they sit at.
+    table: Option<usize>,
+}
+
+/// The lobby as the server keeps it: everyone connected and every game, by its number.
+struct Server {
+    clients: HashMap<usize, Client>,
+    tables: BTreeMap<usize, Table>,
+    next_table: usize,
+    /// Game `n` is saved to this path with `-n` added, see `save::numbered`.
+    save_path: String,
+    feed: Option<Feed>,
+    /// The game shown to the spectators, which is the first one started while none was.
+    watched: Option<usize>,
+}
+
+impl Server {
+    fn send(&mut self, conn: usize, text: &str) {
+        if let Some(client) = self.clients.get_mut(&conn) {
+            let mut lines: String = text.lines().map(|line| format!("{}\n", line)).collect();
+            if lines.is_empty() {
+                lines.push('\n');
+            }
+            if client.stream.write_all(lines.as_bytes()).is_err() {
+                // Shutting the connection down ends its reader thread, which reports it gone
+                // and has it cleaned up like any other disconnection.
+                let _ = client.stream.shutdown(Shutdown::Both);
+            }
+        }
+    }
+
+    /// Sends `text` to everyone at game `id`.
+    fn broadcast(&mut self, id: usize, text: &str) {
+        for line in text.lines() {
+            println!("[{}] {}", id, line);
+        }
+        let conns: Vec<usize> = self.tables[&id].seats.iter().flatten().copied().collect();
+        for conn in conns {
+            self.send(conn, text);
+        }
+    }
+
+    fn list(&mut self, conn: usize) {
+        let text = if self.tables.is_empty() {
+            tr!("lobby.no_games")
+        } else {
+            let games: Vec<String> = self
+                .tables
+                .iter()
+                .map(|(id, table)| table.describe(*id))
+                .collect();
+            games.join("\n")
+        };
+        self.send(conn, &text);
+    }
+
+    fn connected(&mut self, conn: usize, stream: TcpStream) {
+        if stream.set_write_timeout(Some(WRITE_TIMEOUT)).is_err() {
+            let _ = stream.shutdown(Shutdown::Both);
+            return;
+        }
+        self.clients.insert(
+            conn,
+            Client {
+                stream,
+                name: None,
+                table: None,
+            },
+        );
+        self.send(conn, &tr!("net.hello"));
+    }
+
+    fn disconnected(&mut self, conn: usize) {
+        self.stand(conn);
+        self.clients.remove(&conn);
+        self.prune();
+    }
+
+    /// Closes the games made in the lobby that nobody sits at, once whoever created them has
+    /// gone or sat down somewhere.
+    fn prune(&mut self) {
+        let clients = &self.clients;
+        self.tables.retain(|_, table| {
+            !table.empty()
+                || table.host.is_some_and(|host| {
+                    clients
+                        .get(&host)
+                        .is_some_and(|client| client.table.is_none())
+                })
+        });
+    }
+
+    /// Takes `conn` away from its game. Before the game starts its seat is given up, unless
+    /// the host named the player; once it has started the seat waits for them to rejoin.
+    fn stand(&mut self, conn: usize) {
+        let Some(id) = self
+            .clients
+            .get_mut(&conn)
+            .and_then(|client| client.table.take())
+        else {
+            return;
+        };
+        let table = self.tables.get_mut(&id).unwrap();
+        let Some(seat) = table.seat(conn) else {
+            return;
+        };
+        let name = table.players[seat].0.clone();
+        if table.game.is_none() && !table.named {
+            table.players.remove(seat);
+            table.seats.remove(seat);
+            table.tokens.remove(seat);
+        } else {
+            table.seats[seat] = None;
+        }
+        let started = table.game.is_some();
+        self.broadcast(id, &tr!("net.left", name));
+        if started {
+            self.skip_absent(id);
+            self.update(id);
+        }
+        self.prune();
+    }
+
+    /// Carries out `command` for `seat` at game `id` and keeps it in the save.
+    fn perform(&mut self, id: usize, seat: usize, command: &Command) -> Result<(), String> {
+        let table = self.tables.get_mut(&id).unwrap();
+        let Some(seeded) = &mut table.game else {
+            return Err(table.waiting());
+        };
+        seeded.apply(seat, command)?;
+        if let Some(Err(error)) = table.save.as_mut().map(|save| save.action(seat, command)) {
+            eprintln!("{}", error);
+        }
+        Ok(())
+    }
+
+    /// Nobody waits on a player who is away to answer an offer: it counts as declined. Their
+    /// own turn and discards wait until they are back.
+    fn skip_absent(&mut self, id: usize) {
+        let table = &self.tables[&id];
+        let Some(Seeded { game, .. }) = &table.game else {
+            return;
+        };
+        if let Phase::Offer { to, .. } = game.phase {
+            let declined = tr!("net.declined_away", game.players[to].name());
+            if table.seats[to].is_none() && self.perform(id, to, &Command::Decline).is_ok() {
+                self.broadcast(id, &declined);
+            }
+        }
+    }
+
+    fn line(&mut self, conn: usize, line: &str) {
+        let line = line.trim();
+        let Some(client) = self.clients.get_mut(&conn) else {
+            return;
+        };
+        if line.is_empty() {
+            return;
+        }
+        if client.name.is_none() {
+            client.name = Some(line.to_owned());
+            let welcome = tr!("lobby.welcome", line, tr!("lobby.usage"));
+            self.send(conn, &welcome);
+            return self.list(conn);
+        }
+        let Some(id) = client.table else {
+            return self.lobby(conn, line);
+        };
+        let seat = self.tables[&id].seat(conn).unwrap();
+        match (lobby::parse(line), self.tables[&id].game.is_some()) {
+            (Ok(LobbyCommand::Leave), _) => {
+                self.stand(conn);
+                self.send(conn, &tr!("lobby.back"));
+                self.list(conn);
+            }
+            (Ok(LobbyCommand::List), _) => self.list(conn),
+            (Ok(LobbyCommand::Rejoin(id, token)), _) => self.rejoin(conn, id, &token),
+            (Ok(LobbyCommand::Start), false) => self.start(id, conn),
+            (_, false) => {
+                let waiting = self.tables[&id].waiting();
+                self.send(conn, &waiting);
+            }
+            (_, true) => self.command(conn, id, seat, line),
+        }
+    }
+
+    /// Answers what is typed by someone who is not at a game.
+    fn lobby(&mut self, conn: usize, line: &str) {
+        match lobby::parse(line) {
+            Ok(LobbyCommand::List) => self.list(conn),
+            Ok(LobbyCommand::Create(size, difficulty)) => {
+                let id = self.next_table;
+                self.next_table += 1;
+                let seed = rand::random();
+                let table = Table {
+                    host: Some(conn),
+                    ..Table::open(size, difficulty, seed)
+                };
+                self.tables.insert(id, table);
+                println!("[{}] {}", id, tr!("lobby.created", id, size, difficulty));
+                let created = tr!("lobby.created", id, size, difficulty);
+                self.send(conn, &created);
+                let free = self.tables[&id].free_roles().join(", ");
+                self.send(conn, &tr!("lobby.how_to_sit", id, free));
+            }
+            Ok(LobbyCommand::Sit(id, role)) => self.sit(conn, id, &role),
+            Ok(LobbyCommand::Rejoin(id, token)) => self.rejoin(conn, id, &token),
+            Ok(LobbyCommand::Start | LobbyCommand::Leave) => {
+                self.send(conn, &error(&tr!("lobby.not_seated")))
+            }
+            Err(message) => self.send(conn, &error(&message)),
+        }
+    }
+
+    /// Seats `conn` at game `id`, as the player the host named after them or in `role`.
+    fn sit(&mut self, conn: usize, id: usize, role: &str) {
+        let name = self.clients[&conn].name.clone().unwrap_or_default();
+        let Some(table) = self.tables.get_mut(&id) else {
+            return self.send(conn, &error(&tr!("lobby.no_game", id)));
+        };
+        let seat = if table.game.is_some() {
+            Err(tr!("lobby.started", id))
+        } else if table.named {
+            table
+                .players
+                .iter()
+                .zip(&table.seats)
+                .position(|((named, _), seat)| {
+                    seat.is_none() && named.to_lowercase() == name.to_lowercase()
+                })
+                .ok_or_else(|| tr!("net.unknown_player", name, table.waiting()))
+        } else if table.players.len() == table.size {
+            Err(tr!("lobby.full", id))
+        } else {
+            let taken: Vec<String> = table.players.iter().map(|(name, _)| name.clone()).collect();
+            let free = table.free_roles().join(", ");
+            check_name(&name, &taken)
+                .and_then(|()| match role {
+                    "" => Err(tr!("lobby.how_to_sit", id, free)),
+                    _ => Role::from_name(role)
+                        .filter(|role| !table.players.iter().any(|(_, taken)| taken == role))
+                        .ok_or_else(|| tr!("lobby.role_unavailable", role, free)),
+                })
+                .map(|role| {
+                    table.players.push((name.clone(), role));
+                    table.seats.push(None);
+                    table.tokens.push(None);
+                    table.players.len() - 1
+                })
+        };
+        let seat = match seat {
+            Ok(seat) => seat,
+            Err(message) => return self.send(conn, &error(&message)),
+        };
+        let token = format!("{:016x}", rand::random::<u64>());
+        table.host = None;
+        table.seats[seat] = Some(conn);
+        table.tokens[seat] = Some(token.clone());
+        let role = table.players[seat].1;
+        self.clients.get_mut(&conn).unwrap().table = Some(id);
+        self.prune();
+        self.send(conn, &tr!("net.token", token, id));
+        self.broadcast(id, &tr!("lobby.joined", name, role.name()));
+        let waiting = self.tables[&id].waiting();
+        self.broadcast(id, &waiting);
+    }
+
+    /// Puts `conn` back in the seat `token` was given out for at game `id`, taking over from a
+    /// connection that went quiet.
+    fn rejoin(&mut self, conn: usize, id: usize, token: &str) {
+        let seat_for = |tables: &BTreeMap<usize, Table>| {
+            tables.get(&id).map(|table| {
+                table
+                    .tokens
+                    .iter()
+                    .position(|given| given.as_deref() == Some(token))
+            })
+        };
+        match seat_for(&self.tables) {
+            None => return self.send(conn, &error(&tr!("lobby.no_game", id))),
+            Some(None) => return self.send(conn, &error(&tr!("lobby.bad_token", id))),
+            Some(Some(seat)) if self.tables[&id].seats[seat] == Some(conn) => {}
+            // Whatever seat `conn` had before is given up, so no other game keeps waiting on it.
+            Some(Some(_)) => self.stand(conn),
+        }
+        let Some(Some(seat)) = seat_for(&self.tables) else {
+            return self.send(conn, &error(&tr!("lobby.bad_token", id)));
+        };
+        let table = self.tables.get_mut(&id).unwrap();
+        // The old connection goes back to the lobby; its reader thread finds it closed soon
+        // enough if it is gone.
+        if let Some(old) = table.seats[seat].replace(conn) {
+            if let Some(client) = self.clients.get_mut(&old) {
+                client.table = None;
+            }
+        }
+        let name = self.tables[&id].players[seat].0.clone();
+        let client = self.clients.get_mut(&conn).unwrap();
+        client.name = Some(name.clone());
+        client.table = Some(id);
+        let table = self.tables.get_mut(&id).unwrap();
+        table.regathering &= !table.full();
+        self.prune();
+        self.broadcast(id, &tr!("net.rejoined", name));
+        // The status comes first; the update then tells everyone what the game needs next.
+        let status = self.tables[&id].status(seat);
+        self.send(conn, &status);
+        self.update(id);
+    }
+
+    /// Deals game `id` once everyone has sat down.
+    fn start(&mut self, id: usize, conn: usize) {
+        let table = self.tables.get_mut(&id).unwrap();
+        if !table.full() {
+            let waiting = table.waiting();
+            return self.send(conn, &waiting);
+        }
+        let setup = Setup {
+            players: table.players.clone(),
+            difficulty: table.difficulty,
+        };
+        let seeded = Seeded::deal(&setup, table.seed);
+        let game = &seeded.game;
+        // The game seats the players in the order they take turns; the seats here follow it,
+        // so a seat means the same player to the game, the save and the table.
+        let order: Vec<usize> = game
+            .players
+            .iter()
+            .map(|player| {
+                let name = player.plain_name();
+                table
+                    .players
+                    .iter()
+                    .position(|(sat, _)| sat == name)
+                    .unwrap()
+            })
+            .collect();
+        table.players = order
+            .iter()
+            .map(|&seat| table.players[seat].clone())
+            .collect();
+        table.seats = order.iter().map(|&seat| table.seats[seat]).collect();
+        table.tokens = order
+            .iter()
+            .map(|&seat| table.tokens[seat].clone())
+            .collect();
+        let path = save::numbered(&self.save_path, id);
+        let saved = Save::create(&path, &setup, table.seed).and_then(|mut save| {
+            for (seat, token) in table.tokens.iter().enumerate() {
+                if let Some(token) = token {
+                    save.token(seat, token)?;
+                }
+            }
+            Ok(save)
+        });
+        match saved {
+            Ok(save) => table.save = Some(save),
+            Err(error) => eprintln!("{}: {}", path, error),
+        }
+        table.logged = game.board.log.entries().len();
+        table.game = Some(seeded);
+        println!("[{}] {}", id, tr!("lobby.seed", table.seed));
+        self.broadcast(id, &tr!("net.started"));
+        let table = &self.tables[&id];
+        let statuses: Vec<(usize, String)> = (0..table.seats.len())
+            .filter_map(|seat| Some((table.seats[seat]?, table.status(seat))))
+            .collect();
+        for (conn, status) in statuses {
+            self.send(conn, &status);
+        }
+        self.update(id);
+    }
+
+    fn command(&mut self, conn: usize, id: usize, seat: usize, line: &str) {
+        match command::parse(line) {
+            Ok(Command::Status) => {
+                let status = self.tables[&id].status(seat);
+                self.send(conn, &status);
+            }
+            Ok(Command::Log) => {
+                let lines = self.tables[&id]
+                    .game
+                    .as_ref()
+                    .map(|seeded| seeded.game.board.log.lines())
+                    .unwrap_or_default();
+                let text = if lines.is_empty() {
+                    tr!("log.empty")
+                } else {
+                    lines.join("\n")
+                };
+                self.send(conn, &text);
+            }
+            Ok(Command::Help(_)) => self.send(conn, &tr!("command.usage")),
+            // Talk changes nothing anyone has to do, so only the new lines go out.
+            Ok(command @ (Command::Say(_) | Command::Note(..))) => {
+                match self.perform(id, seat, &command) {
+                    Ok(()) => {
+                        self.catch_up(id);
+                        self.spectate(id);
+                    }
+                    Err(message) => self.send(conn, &error(&message)),
+                }
+            }
+            Ok(_) if self.tables[&id].regathering => {
+                let waiting = self.tables[&id].waiting();
+                self.send(conn, &error(&waiting));
+            }
+            Ok(command) => match self.perform(id, seat, &command) {
+                Ok(()) => {
+                    self.skip_absent(id);
+                    self.update(id);
+                }
+                Err(message) => self.send(conn, &error(&message)),
+            },
+            Err(message) => self.send(conn, &error(&message)),
+        }
+    }
+
+    /// Sends everyone at game `id` what happened since they were last told.
+    fn catch_up(&mut self, id: usize) {
+        let table = self.tables.get_mut(&id).unwrap();
+        let Some(Seeded { game, .. }) = &table.game else {
+            return;
+        };
+        let entries: Vec<String> = game.board.log.entries()[table.logged..]
+            .iter()
+            .map(|entry| format!("  {}", entry))
+            .collect();
+        table.logged += entries.len();
+        for entry in entries {
+            self.broadcast(id, &entry);
+        }
+    }
+
+    /// Sends everyone at game `id` what happened since the last update and what the game now
+    /// needs from them. A game that is over is closed and its players go back to the lobby.
+    fn update(&mut self, id: usize) {
+        self.catch_up(id);
+        let table = &self.tables[&id];
+        let views: Vec<(usize, String)> = (0..table.seats.len())
+            .filter_map(|seat| Some((table.seats[seat]?, table.view(seat))))
+            .collect();
+        for (conn, view) in views {
+            self.send(conn, &view);
+        }
+        self.spectate(id);
+
+        let table = &self.tables[&id];
+        if let Some(Phase::Over(_)) = table.game.as_ref().map(|seeded| seeded.game.phase) {
+            let conns: Vec<usize> = table.seats.iter().flatten().copied().collect();
+            for conn in conns {
+                if let Some(client) = self.clients.get_mut(&conn) {
+                    client.table = None;
+                }
+                self.send(conn, &tr!("lobby.back"));
+            }
+            self.tables.remove(&id);
+            if self.watched == Some(id) {
+                self.watched = None;
+                if let Some(feed) = &mut self.feed {
+                    feed.restart();
+                }
+            }
+        }
+    }
+
+    fn spectate(&mut self, id: usize) {
+        let Some(feed) = &mut self.feed else {
+            return;
+        };
+        if *self.watched.get_or_insert(id) != id {
+            return;
+        }
+        if let Some(Seeded { game, .. }) = &self.tables[&id].game {
+            let players: Vec<&Player> = game.players.iter().collect();
+            feed.update(&game.board, &players);
+            if let Phase::Over(outcome) = game.phase {
+                feed.announce(&outcome.to_string());
+            }
+        }
+    }
+}
+
+/// Hosts a lobby on `addr`, where players at other terminals create games, sit down at them
+/// and play. Every move is saved as it happens, and the unfinished games found in the saves are
+/// picked up again. With players given on the command line, the first game is set up for them.
+pub fn serve(addr: &str, options: &Options) -> io::Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    // The players see what happens in their games from the log; the host's screen would only
+    // mix the games up.
+    game::set_quiet(true);
+    let mut server = Server {
+        clients: HashMap::new(),
+        tables: BTreeMap::new(),
+        next_table: 1,
+        save_path: options
+            .save
+            .clone()
+            .unwrap_or_else(|| save::DEFAULT_PATH.to_owned()),
+        feed: options.spectate.as_deref().map(Feed::open).transpose()?,
+        watched: None,
+    };
+    // Ids may have gaps where lobby games closed before they started, so every save is
+    // looked for and new games are numbered after the last one.
+    for id in save::numbered_ids(&server.save_path)? {
+        let path = save::numbered(&server.save_path, id);
+        server.next_table = id + 1;
+        let Some(loaded) = save::load(&path)? else {
+            continue;
+        };
+        if !matches!(loaded.seeded.game.phase, Phase::Over(_)) {
+            println!("{}", tr!("net.resumed", path));
+            let table = Table::resumed(loaded, Save::append(&path)?);
+            server.tables.insert(id, table);
+        }
+    }
+    if let Some(players) = &options.players {
+        let difficulty = options.difficulty.unwrap_or(Difficulty::Standard);
+        let seed = options.seed.unwrap_or_else(rand::random);
+        let table = Table::named(setup::deal_roles(players, difficulty), seed);
+        server.tables.insert(server.next_table, table);
+        server.next_table += 1;
+    }
+    println!("{}", tr!("net.listening", listener.local_addr()?));
+
+    let (sender, messages) = mpsc::channel();
+    thread::spawn(move || accept(listener, sender));
+    let resumed: Vec<usize> = server.tables.keys().copied().collect();
+    for id in resumed {
+        server.spectate(id);
+    }
+    for message in messages {
+        match message {
+            Message::Connected(conn, stream) => server.connected(conn, stream),
+            Message::Line(conn, line) => server.line(conn, &line),
+            Message::Disconnected(conn) => server.disconnected(conn),
+        }
+    }
+    Ok(())
+}
+
+/// Plays on a game hosted elsewhere: typed lines go to the server and its replies are printed.
+pub fn join(addr: &str) -> io::Result<()> {
+    let stream = TcpStream::connect(addr)?;
+    println!("{}", tr!("net.connected", addr));
+    let mut writer = stream.try_clone()?;
+    thread::spawn(move || {
+        for line in io::stdin().lock().lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if writeln!(writer, "{}", line).is_err() {
+                break;
+            }
+        }
+    });
+    for line in BufReader::new(stream).lines() {
+        println!("{}", line?);
+    }
+    println!("{}", tr!("net.disconnected"));
+    Ok(())
+}
@@ -1,16 +1,35 @@
-use std::u32::MIN;
-
-use crossterm::style::{StyledContent, Stylize};
+use crossterm::style::{Color as CrosstermColor, StyledContent, Stylize};
 
 use crate::{
     board::{self, Board, Cities, DiseaseState},
+    command::{find_player, Command},
     common::Color,
-    menu::menu_cancelable,
+    cure_plan::show_cure_plan,
+    game::{say, talk},
+    help::show_help,
+    i18n::tr,
+    infection_risk::show_infection_risk,
+    log::{show_log, Entry},
+    menu::{menu, menu_cancelable, menu_injectable},
     player_card::{Event, PlayerCard},
     role::Role,
+    route::{plan_route, Movement, Step},
+    status::show_status,
+    theme::Themed,
+    tui::show_world_map,
 };
 
 pub const MAX_CARDS_IN_HAND: usize = 7;
+pub const ACTIONS_PER_TURN: u8 = 4;
+pub const CARDS_TO_CURE: usize = 5;
+pub const CARDS_TO_CURE_SCIENTIST: usize = 4;
+
+/// What was picked in a movement menu.
+enum Destination {
+    City(Cities),
+    /// Gone back, looked at a route or travelled along one, using this many actions.
+    Handled(u8),
+}
 
 #[derive(Debug, Clone)]
 pub struct Player {
@@ -30,13 +49,32 @@ impl Player {
         }
     }
 
+    /// The same player standing in `city`, for tests that need pawns spread over the board.
+    #[cfg(test)]
+    pub(crate) fn at(mut self, city: Cities) -> Self {
+        self.location = city;
+        self
+    }
+
+    /// The same player holding the City cards for `cities`, for tests.
+    #[cfg(test)]
+    pub(crate) fn holding(mut self, cities: &[Cities]) -> Self {
+        let mut deck = crate::deck::Deck::<PlayerCard>::new();
+        while let Some(card) = deck.draw_from_top() {
+            if matches!(&card, PlayerCard::CityCard(city) if cities.contains(&city.city)) {
+                self.add_to_hand(card);
+            }
+        }
+        self
+    }
+
     pub fn add_to_hand(&mut self, card: PlayerCard) {
         self.hand.push(card);
         self.hand.sort_unstable();
     }
 
     pub fn max_population_city(&self) -> u32 {
-        let mut max = MIN;
+        let mut max = u32::MIN;
         for card in &self.hand {
             max = std::cmp::max(
                 max,
@@ -50,239 +88,526 @@ impl Player {
     }
 
     pub fn name(&self) -> StyledContent<String> {
-        self.name.clone().bold().with(self.role.color())
+        self.name.clone().bold().with(self.role.color()).themed()
+    }
+
+    pub fn plain_name(&self) -> &str {
+        &self.name
+    }
+
+    /// The letter used for this player's pawn on the world map.
+    pub fn initial(&self) -> char {
+        self.name
+            .chars()
+            .next()
+            .map_or('?', |ch| ch.to_ascii_uppercase())
+    }
+
+    pub fn location(&self) -> Cities {
+        self.location
+    }
+
+    pub fn role(&self) -> Role {
+        self.role
+    }
+
+    pub fn cards_to_cure(&self) -> usize {
+        match self.role {
+            Role::Scientist => CARDS_TO_CURE_SCIENTIST,
+            _ => CARDS_TO_CURE,
+        }
+    }
+
+    pub fn cards_of_color(&self, color: Color) -> Vec<Cities> {
+        let mut city_cards = Vec::new();
+        for card in &self.hand {
+            if let PlayerCard::CityCard(city) = card {
+                if city.color == color {
+                    city_cards.push(city.city)
+                }
+            }
+        }
+        city_cards
+    }
+
+    pub fn city_cards(&self) -> Vec<Cities> {
+        let mut city_cards = Vec::new();
+        for card in &self.hand {
+            if let PlayerCard::CityCard(city) = card {
+                city_cards.push(city.city)
+            }
+        }
+        city_cards
+    }
+
+    /// Moves the pawn and notes it in the game log.
+    fn move_to(&mut self, board: &mut Board, to: Cities) {
+        self.location = to;
+        board.log.record(Entry::Moved(self.name.clone(), to));
+    }
+
+    fn discard_city_card(&mut self, board: &mut Board, city: Cities) -> bool {
+        let position = self.hand.iter().position(|card| match card {
+            PlayerCard::CityCard(card) => card.city == city,
+            _ => false,
+        });
+        match position {
+            Some(idx) => {
+                board.player_discard.discard_to_top(self.hand.remove(idx));
+                true
+            }
+            None => false,
+        }
     }
 
     pub fn actions(&self) -> Vec<String> {
-        let mut actions = Vec::from([
-            "Drive / Ferry (Cost: 1 action)".to_owned(),
-            "Direct Flight (Cost: 1 action)".to_owned(),
-            "Charter Flight (Cost: 1 action)".to_owned(),
-            "Shuttle Flight (Cost: 1 action)".to_owned(),
-            "Build a Research Station (Cost: 1 action)".to_owned(),
-            "Treat Disease (Cost: 1 action)".to_owned(),
-            "Share Knowledge (Cost: 1 action)".to_owned(),
-            "Discover a Cure (Cost: 1 action)".to_owned(),
-        ]);
-        actions
-    }
-
-    pub fn act(&mut self, board: &mut Board, players: &mut [Player], action: usize) -> bool {
+        Vec::from([
+            tr!("action.drive"),
+            tr!("action.direct_flight"),
+            tr!("action.charter"),
+            tr!("action.shuttle"),
+            tr!("action.build"),
+            tr!("action.treat"),
+            tr!("action.share"),
+            tr!("action.cure"),
+            tr!("action.view_risk"),
+            tr!("action.view_cure_plan"),
+            tr!("action.view_map"),
+            tr!("action.view_status"),
+            tr!("action.help"),
+            tr!("action.view_log"),
+        ])
+    }
+
+    /// Performs the action chosen from `actions()` and returns how many actions it used.
+    pub fn act(
+        &mut self,
+        board: &mut Board,
+        players: &mut [Player],
+        action: usize,
+        actions_left: u8,
+    ) -> u8 {
         match action {
-            0 => true,
-            1 => self.drive_ferry(board),
-            2 => self.direct_flight(board),
-            3 => self.chartered_flight(board),
-            4 => self.shuttle_flight(board),
-            5 => self.build_research_station(board),
-            6 => self.treat_disease(board),
-            7 => self.share_knowledge(players),
-            8 => self.discover_cure(board),
-            _ => false,
+            0 => 1,
+            1 => self.drive_ferry(board, actions_left),
+            2 => self.direct_flight(board, actions_left),
+            3 => self.chartered_flight(board, actions_left),
+            4 => self.shuttle_flight(board, actions_left),
+            5 => self.build_research_station(board) as u8,
+            6 => self.treat_disease(board) as u8,
+            7 => self.share_knowledge(board, players) as u8,
+            8 => self.discover_cure(board) as u8,
+            9 => {
+                show_infection_risk(board);
+                0
+            }
+            10 => {
+                show_cure_plan(board, self, players);
+                0
+            }
+            11 => {
+                if let Err(error) = show_world_map(board, self, players) {
+                    println!(
+                        "{}: {}",
+                        tr!("error").with(CrosstermColor::Red).bold().themed(),
+                        tr!("map.failed", error)
+                    );
+                }
+                0
+            }
+            12 => {
+                show_status(board, self, players);
+                0
+            }
+            13 => {
+                show_help(self, players, "");
+                0
+            }
+            14 => {
+                show_log(&board.log);
+                0
+            }
+            _ => 0,
+        }
+    }
+
+    /// Carries out a typed command and returns how many actions it used.
+    pub fn perform(
+        &mut self,
+        board: &mut Board,
+        players: &mut [Player],
+        command: &Command,
+        actions_left: u8,
+    ) -> u8 {
+        let step = |movement, to, card| Step { movement, to, card };
+        let done = match command {
+            Command::Pass => true,
+            Command::Drive(to) => self.travel(board, &step(Movement::DriveFerry, *to, None)),
+            Command::Fly(to) => self.travel(board, &step(Movement::DirectFlight, *to, Some(*to))),
+            Command::Charter(to) => self.travel(
+                board,
+                &step(Movement::CharterFlight, *to, Some(self.location)),
+            ),
+            Command::Shuttle(to) => self.travel(board, &step(Movement::ShuttleFlight, *to, None)),
+            Command::Travel(to) => return self.travel_route(board, *to, actions_left),
+            Command::Build(None) => self.build_research_station(board),
+            Command::Build(Some(from)) => self.build(board, Some(*from)),
+            Command::Treat(color) => {
+                self.treat(board, color.unwrap_or_else(|| self.location.color()))
+            }
+            Command::Give(card, name) | Command::Take(card, name) => {
+                let names: Vec<&str> = players.iter().map(|player| player.plain_name()).collect();
+                match find_player(name, &names) {
+                    Ok(idx) => match command {
+                        Command::Give(..) => self.share(board, &mut players[idx], *card, true),
+                        _ => self.share(board, &mut players[idx], *card, false),
+                    },
+                    Err(error) => {
+                        say!(
+                            "{}: {}",
+                            tr!("error").with(CrosstermColor::Red).bold().themed(),
+                            error
+                        );
+                        return 0;
+                    }
+                }
+            }
+            Command::Cure(color) => self.cure(board, *color),
+            Command::Help(topic) => {
+                show_help(self, players, topic);
+                return 0;
+            }
+            Command::Log => {
+                show_log(&board.log);
+                return 0;
+            }
+            Command::Status => {
+                show_status(board, self, players);
+                return 0;
+            }
+            Command::Play(event) => {
+                match self.hand.iter().position(
+                    |card| matches!(card, PlayerCard::EventCard(held) if held.event() == *event),
+                ) {
+                    Some(idx) => {
+                        if let PlayerCard::EventCard(event) = self.hand.remove(idx) {
+                            self.play_event(board, event);
+                        }
+                    }
+                    None => say!(
+                        "{}: {}",
+                        tr!("error").with(CrosstermColor::Red).bold().themed(),
+                        tr!("game.no_card", event)
+                    ),
+                }
+                return 0;
+            }
+            Command::Say(_) | Command::Note(..) => {
+                let names: Vec<&str> = std::iter::once(self.plain_name())
+                    .chain(players.iter().map(Player::plain_name))
+                    .collect();
+                if let Err(message) = talk(board, self.plain_name(), &names, command) {
+                    say!(
+                        "{}: {}",
+                        tr!("error").with(CrosstermColor::Red).bold().themed(),
+                        message
+                    );
+                }
+                return 0;
+            }
+            Command::Accept | Command::Decline | Command::Discard(_) | Command::DiscardEvent(_) => {
+                say!(
+                    "{}: {}",
+                    tr!("error").with(CrosstermColor::Red).bold().themed(),
+                    tr!("game.not_now")
+                );
+                return 0;
+            }
+        };
+        if !done {
+            say!(
+                "{}: {}",
+                tr!("error").with(CrosstermColor::Red).bold().themed(),
+                tr!("command.illegal", self.name(), self.location)
+            );
         }
+        done as u8
     }
 
     pub fn play_event(&mut self, board: &mut Board, event: Event) -> bool {
-        println!("{} played {}. TODO Implementation ;)", self.name(), &event);
+        say!("{}", tr!("event.played", self.name(), event));
+        board
+            .log
+            .record(Entry::EventPlayed(self.name.clone(), event.event()));
         board
             .player_discard
             .discard_to_top(PlayerCard::EventCard(event));
         true
     }
 
-    pub fn drive_ferry(&mut self, board: &mut Board) -> bool {
-        let mut adjacent_cities = Vec::from_iter(board.adjacent_to(self.location).unwrap());
-        adjacent_cities.sort_unstable();
-        let selection = menu_cancelable(
-            format!(
-                "{}'s Drive / Ferry Menu from {}",
-                self.name(),
-                self.location
-            )
-            .as_str(),
-            &adjacent_cities,
-        );
-        if selection == 0 {
-            false
-        } else {
-            self.location = adjacent_cities[selection - 1];
-            true
+    /// A movement menu: the destinations in `cities`, then the route planner and the Travel
+    /// To macro, which are always there as the last two entries.
+    fn movement_menu(
+        &mut self,
+        board: &mut Board,
+        title: &str,
+        cities: &[Cities],
+        actions_left: u8,
+    ) -> Destination {
+        let mut options: Vec<String> = cities.iter().map(ToString::to_string).collect();
+        options.push(tr!("action.plan_route"));
+        options.push(tr!("action.travel_to"));
+        let selection = menu_cancelable(title, &options);
+        match selection.checked_sub(1) {
+            None => Destination::Handled(0),
+            Some(idx) if idx < cities.len() => Destination::City(cities[idx]),
+            Some(idx) if idx == cities.len() => {
+                self.plan_route(board);
+                Destination::Handled(0)
+            }
+            Some(_) => Destination::Handled(self.travel_to(board, actions_left)),
         }
     }
 
-    pub fn direct_flight(&mut self, board: &mut Board) -> bool {
-        let mut city_cards = Vec::new();
-        for card in &self.hand {
-            match card {
-                PlayerCard::CityCard(city) => city_cards.push(city.city),
-                _ => (),
+    pub fn drive_ferry(&mut self, board: &mut Board, actions_left: u8) -> u8 {
+        let mut adjacent_cities = Vec::from_iter(board.adjacent_to(self.location).unwrap());
+        adjacent_cities.sort_unstable();
+        let title = tr!("menu.drive", self.name(), self.location);
+        match self.movement_menu(board, &title, &adjacent_cities, actions_left) {
+            Destination::City(to) => {
+                self.move_to(board, to);
+                1
             }
+            Destination::Handled(used) => used,
         }
+    }
+
+    pub fn direct_flight(&mut self, board: &mut Board, actions_left: u8) -> u8 {
+        let mut city_cards = self.city_cards();
         city_cards.sort_unstable();
-        let selection = menu_cancelable(
-            format!(
-                "{}'s Direct Flight Menu from {}",
-                self.name(),
-                self.location
-            )
-            .as_str(),
-            &city_cards,
-        );
-        if selection == 0 {
-            false
-        } else {
-            self.location = city_cards[selection - 1];
-            let mut i = 0;
-            while i < self.hand.len() {
-                if let PlayerCard::CityCard(city) = &self.hand[i] {
-                    if city.city == self.location {
-                        board.player_discard.discard_to_top(self.hand.remove(i));
-                    } else {
-                        i += 1;
-                    }
-                } else {
-                    i += 1;
-                }
+        let title = tr!("menu.direct_flight", self.name(), self.location);
+        match self.movement_menu(board, &title, &city_cards, actions_left) {
+            Destination::City(to) => {
+                self.move_to(board, to);
+                self.discard_city_card(board, self.location) as u8
             }
-            true
+            Destination::Handled(used) => used,
         }
     }
 
-    pub fn chartered_flight(&mut self, board: &mut Board) -> bool {
+    pub fn chartered_flight(&mut self, board: &mut Board, actions_left: u8) -> u8 {
         let mut city_cards = Vec::new();
         for card in &self.hand {
-            match card {
-                PlayerCard::CityCard(city) => {
-                    if city.city == self.location {
-                        city_cards.push(city.city);
-                    }
+            if let PlayerCard::CityCard(city) = card {
+                if city.city == self.location {
+                    city_cards.push(city.city);
                 }
-                _ => (),
             }
         }
-        let selection = menu_cancelable("Consume a card to take a Chartered Flight?", &city_cards);
+        let selection = menu_cancelable(&tr!("menu.charter_card"), &city_cards);
         if selection == 0 {
-            false
-        } else {
-            let mut cities = board.all_cities();
-            cities.sort_unstable();
-            let selection = menu_cancelable(
-                format!(
-                    "{}'s Charter Flight Menu from {}",
-                    self.name(),
-                    self.location
-                )
-                .as_str(),
-                &cities,
-            );
-            if selection == 0 {
-                false
-            } else {
-                let mut i = 0;
-                while i < self.hand.len() {
-                    if let PlayerCard::CityCard(city) = &self.hand[i] {
-                        if city.city == self.location {
-                            board.player_discard.discard_to_top(self.hand.remove(i));
-                        } else {
-                            i += 1;
-                        }
-                    } else {
-                        i += 1;
-                    }
-                }
-                self.location = cities[selection - 1];
-                true
+            return 0;
+        }
+        let mut cities = board.all_cities();
+        cities.sort_unstable();
+        let title = tr!("menu.charter", self.name(), self.location);
+        match self.movement_menu(board, &title, &cities, actions_left) {
+            Destination::City(to) => {
+                self.discard_city_card(board, self.location);
+                self.move_to(board, to);
+                1
             }
+            Destination::Handled(used) => used,
         }
     }
 
-    pub fn shuttle_flight(&mut self, board: &mut Board) -> bool {
+    pub fn shuttle_flight(&mut self, board: &mut Board, actions_left: u8) -> u8 {
         if !board.map.get(&self.location).unwrap().has_research_station {
-            println!("{}'s current city, {}, does not have a research station, so shuttle flight is not available.", self.name(), &self.location);
-            return false;
+            println!("{}", tr!("shuttle.no_station", self.name(), self.location));
+            return 0;
         }
-        let mut cities_with_research_stations = Vec::new();
-        for city in board.map.values() {
-            if city.has_research_station {
-                cities_with_research_stations.push(city.city.clone());
+        let cities_with_research_stations = board.research_stations();
+        let title = tr!("menu.shuttle", self.name(), self.location);
+        match self.movement_menu(board, &title, &cities_with_research_stations, actions_left) {
+            Destination::City(to) => {
+                self.move_to(board, to);
+                1
             }
+            Destination::Handled(used) => used,
         }
-        cities_with_research_stations.sort_unstable();
-        let selection = menu_cancelable(
-            format!(
-                "{}'s Shuttle Flight Menu from {}",
-                self.name(),
-                self.location
-            )
-            .as_str(),
-            &cities_with_research_stations,
-        );
+    }
+
+    /// Takes a single movement step without prompting, checking that it is legal first.
+    pub fn travel(&mut self, board: &mut Board, step: &Step) -> bool {
+        let legal = match step.movement {
+            Movement::DriveFerry => board.is_adjacent(self.location, step.to),
+            Movement::DirectFlight => {
+                step.card == Some(step.to) && self.city_cards().contains(&step.to)
+            }
+            Movement::CharterFlight => {
+                step.card == Some(self.location) && self.city_cards().contains(&self.location)
+            }
+            Movement::ShuttleFlight => {
+                let stations = board.research_stations();
+                stations.contains(&self.location) && stations.contains(&step.to)
+            }
+        };
+        if !legal || step.to == self.location {
+            return false;
+        }
+        if let Some(card) = step.card {
+            self.discard_city_card(board, card);
+        }
+        self.move_to(board, step.to);
+        true
+    }
+
+    fn choose_destination(
+        &self,
+        board: &Board,
+        title_key: &str,
+    ) -> Option<(Cities, Option<Vec<Step>>)> {
+        let mut cities = board.all_cities();
+        cities.sort_unstable();
+        let selection = menu_cancelable(&tr!(title_key, self.name(), self.location), &cities);
         if selection == 0 {
-            false
+            return None;
+        }
+        let to = cities[selection - 1];
+        Some((to, plan_route(board, self.location, &self.city_cards(), to)))
+    }
+
+    pub fn plan_route(&self, board: &Board) {
+        match self.choose_destination(board, "menu.route_planner") {
+            Some((to, Some(route))) => {
+                let key = if route.len() == 1 {
+                    "route.summary.one"
+                } else {
+                    "route.summary.other"
+                };
+                println!("{}", tr!(key, self.location, to, route.len()));
+                for (i, step) in route.iter().enumerate() {
+                    println!("\t{}. {}", i + 1, step);
+                }
+            }
+            Some((to, None)) => println!("{}", tr!("route.unreachable", self.name(), to)),
+            None => (),
+        }
+    }
+
+    pub fn travel_to(&mut self, board: &mut Board, actions_left: u8) -> u8 {
+        match self.choose_destination(board, "menu.travel") {
+            Some((to, _)) => self.travel_route(board, to, actions_left),
+            None => 0,
+        }
+    }
+
+    /// Plans a route and, once confirmed, walks as much of it as the remaining actions allow.
+    pub fn travel_route(&mut self, board: &mut Board, to: Cities, actions_left: u8) -> u8 {
+        self.route_to(board, to, actions_left, true)
+    }
+
+    /// Walks as much of the shortest route to `to` as the remaining actions allow, without
+    /// asking first.
+    pub fn walk_route(&mut self, board: &mut Board, to: Cities, actions_left: u8) -> u8 {
+        self.route_to(board, to, actions_left, false)
+    }
+
+    fn route_to(&mut self, board: &mut Board, to: Cities, actions_left: u8, confirm: bool) -> u8 {
+        let route = match plan_route(board, self.location, &self.city_cards(), to) {
+            Some(route) if !route.is_empty() => route,
+            _ => {
+                say!("{}", tr!("route.cannot_travel", self.name(), to));
+                return 0;
+            }
+        };
+        let steps = route.len().min(actions_left as usize);
+        say!("{}", tr!("route.header", self.location, to));
+        for (i, step) in route.iter().enumerate() {
+            say!("\t{}. {}", i + 1, step);
+        }
+        let title = if steps < route.len() {
+            tr!("route.confirm_partial", to, route.len(), steps)
         } else {
-            self.location = cities_with_research_stations[selection - 1];
-            true
+            tr!("route.confirm", to, steps)
+        };
+        if confirm
+            && menu_injectable(&title, &tr!("menu.cancel"), &[tr!("route.confirm_option")]) == 0
+        {
+            return 0;
+        }
+        let mut taken = 0;
+        for step in &route[..steps] {
+            if !self.travel(board, step) {
+                break;
+            }
+            say!("{}", tr!("route.takes", self.name(), step));
+            taken += 1;
         }
+        taken
     }
 
     pub fn build_research_station(&mut self, board: &mut Board) -> bool {
         let mut city_cards = Vec::new();
         for card in &self.hand {
-            match card {
-                PlayerCard::CityCard(city) => {
-                    if city.city == self.location {
-                        city_cards.push(city.city);
-                    }
+            if let PlayerCard::CityCard(city) = card {
+                if city.city == self.location {
+                    city_cards.push(city.city);
                 }
-                _ => (),
             }
         }
-        let selection = menu_cancelable(format!("Should {} consume a card to build a Research Station? Currently {}/{} Research Stations", self.name(), board.total_research_stations(), board::MAX_RESEARCH_STATIONS).as_str(), &city_cards);
+        let selection = menu_cancelable(
+            &tr!(
+                "menu.build",
+                self.name(),
+                board.total_research_stations(),
+                board::MAX_RESEARCH_STATIONS
+            ),
+            &city_cards,
+        );
         if selection == 0 {
-            false
-        } else {
-            if board.total_research_stations() >= board::MAX_RESEARCH_STATIONS {
-                let mut cities_with_research_stations = Vec::new();
-                for city in board.map.values() {
-                    if city.has_research_station {
-                        cities_with_research_stations.push(city.city.clone());
-                    }
-                }
-                cities_with_research_stations.sort_unstable();
-                let selection = menu_cancelable(
-                    format!("{} can move a Research Station", self.name()).as_str(),
-                    &cities_with_research_stations,
-                );
-                if selection == 0 {
-                    return false;
-                } else {
-                    match board
-                        .map
-                        .get_mut(&cities_with_research_stations[selection - 1])
-                    {
-                        Some(x) => x.has_research_station = false,
-                        None => (),
-                    }
-                }
+            return false;
+        }
+        let mut moved_from = None;
+        if board.total_research_stations() >= board::MAX_RESEARCH_STATIONS {
+            let cities_with_research_stations = board.research_stations();
+            let selection = menu_cancelable(
+                &tr!("menu.move_station", self.name()),
+                &cities_with_research_stations,
+            );
+            if selection == 0 {
+                return false;
             }
-            let mut i = 0;
-            while i < self.hand.len() {
-                if let PlayerCard::CityCard(city) = &self.hand[i] {
-                    if city.city == self.location {
-                        board.player_discard.discard_to_top(self.hand.remove(i));
-                    } else {
-                        i += 1;
+            moved_from = Some(cities_with_research_stations[selection - 1]);
+        }
+        self.build(board, moved_from)
+    }
+
+    /// Discards the City card of the current city to build a research station there. Once all
+    /// of them are on the board, one has to be moved from `moved_from`.
+    pub fn build(&mut self, board: &mut Board, moved_from: Option<Cities>) -> bool {
+        let stations = board.research_stations();
+        if stations.contains(&self.location) || !self.city_cards().contains(&self.location) {
+            return false;
+        }
+        if board.total_research_stations() >= board::MAX_RESEARCH_STATIONS {
+            match moved_from {
+                Some(from) if stations.contains(&from) => {
+                    if let Some(x) = board.map.get_mut(&from) {
+                        x.has_research_station = false
                     }
-                } else {
-                    i += 1;
                 }
+                _ => return false,
             }
-            match board.map.get_mut(&self.location) {
-                Some(x) => x.has_research_station = true,
-                None => (),
-            }
-            true
         }
+        self.discard_city_card(board, self.location);
+        if let Some(x) = board.map.get_mut(&self.location) {
+            x.has_research_station = true
+        }
+        board
+            .log
+            .record(Entry::Built(self.name.clone(), self.location));
+        true
     }
 
     pub fn treat_disease(&mut self, board: &mut Board) -> bool {
@@ -318,7 +643,8 @@ impl Player {
             Some(city) => {
                 let options = [
                     format!(
-                        "Treat {} ({}/{}) [{}/{}{}]",
+                        "{} {} ({}/{}) [{}/{}{}]",
+                        tr!("treat.option"),
                         Color::Blue,
                         city.blue_infection_count,
                         board::MAX_INFECTION_PER_TYPE_PER_CITY,
@@ -327,7 +653,8 @@ impl Player {
                         blue_status
                     ),
                     format!(
-                        "Treat {} ({}/{}) [{}/{}{}]",
+                        "{} {} ({}/{}) [{}/{}{}]",
+                        tr!("treat.option"),
                         Color::Yellow,
                         city.yellow_infection_count,
                         board::MAX_INFECTION_PER_TYPE_PER_CITY,
@@ -336,7 +663,8 @@ impl Player {
                         yellow_status
                     ),
                     format!(
-                        "Treat {} ({}/{}) [{}/{}{}]",
+                        "{} {} ({}/{}) [{}/{}{}]",
+                        tr!("treat.option"),
                         Color::Black,
                         city.black_infection_count,
                         board::MAX_INFECTION_PER_TYPE_PER_CITY,
@@ -345,7 +673,8 @@ impl Player {
                         black_status
                     ),
                     format!(
-                        "Treat {} ({}/{}) [{}/{}{}]",
+                        "{} {} ({}/{}) [{}/{}{}]",
+                        tr!("treat.option"),
                         Color::Red,
                         city.red_infection_count,
                         board::MAX_INFECTION_PER_TYPE_PER_CITY,
@@ -354,136 +683,248 @@ impl Player {
                         red_status
                     ),
                 ];
-                let selection = menu_cancelable(
-                    format!("{} is Treating Disease in {}", self.name(), &self.location).as_str(),
-                    &options,
-                );
-                match selection {
-                    0 => return false,
-                    1 => {
-                        if city.blue_infection_count > 0 {
-                            if board.blue_disease == board::DiseaseState::Cured {
-                                city.blue_infection_count = 0;
-                            } else {
-                                city.blue_infection_count -= 1;
-                            }
-                        } else {
-                            return false;
-                        }
-                    }
-                    2 => {
-                        if city.yellow_infection_count > 0 {
-                            if board.yellow_disease == board::DiseaseState::Cured {
-                                city.yellow_infection_count = 0;
-                            } else {
-                                city.yellow_infection_count -= 1;
-                            }
-                        } else {
-                            return false;
-                        }
-                    }
-                    3 => {
-                        if city.black_infection_count > 0 {
-                            if board.black_disease == board::DiseaseState::Cured {
-                                city.black_infection_count = 0;
-                            } else {
-                                city.black_infection_count -= 1;
-                            }
-                        } else {
-                            return false;
-                        }
-                    }
-                    4 => {
-                        if city.red_infection_count > 0 {
-                            if board.red_disease == board::DiseaseState::Cured {
-                                city.red_infection_count = 0;
-                            } else {
-                                city.red_infection_count -= 1;
-                            }
-                        } else {
-                            return false;
-                        }
-                    }
+                let selection =
+                    menu_cancelable(&tr!("menu.treat", self.name(), self.location), &options);
+                let color = match selection {
+                    1 => Color::Blue,
+                    2 => Color::Yellow,
+                    3 => Color::Black,
+                    4 => Color::Red,
                     _ => return false,
-                }
+                };
+                self.treat(board, color)
+            }
+            None => false,
+        }
+    }
+
+    /// Removes one cube of `color` from the current city, or all of them once it is cured.
+    pub fn treat(&mut self, board: &mut Board, color: Color) -> bool {
+        let cured = board.disease_state(color) != DiseaseState::Default;
+        let city = board.map.get_mut(&self.location).unwrap();
+        let count = city.infection_count(color);
+        if count == 0 {
+            return false;
+        }
+        city.set_infection_count(color, if cured { 0 } else { count - 1 });
+        board
+            .log
+            .record(Entry::Treated(self.name.clone(), self.location, color));
+        if cured && board.total_cubes(color) == 0 {
+            board.set_disease_state(color, DiseaseState::Eradicated);
+            board.log.record(Entry::Eradicated(color));
+            say!("{}", tr!("disease.eradicated_now", color));
+        }
+        true
+    }
+
+    /// Whether the rules let this player give `card` to `other` by Share Knowledge.
+    pub fn can_give(&self, other: &Player, card: Cities) -> bool {
+        self.location == other.location
+            && (self.role == Role::Researcher || card == self.location)
+            && self.city_cards().contains(&card)
+    }
+
+    /// Gives `card` to `other` by Share Knowledge, if the rules allow it.
+    pub fn give(&mut self, board: &mut Board, other: &mut Player, card: Cities) -> bool {
+        if !self.can_give(other, card) {
+            return false;
+        }
+        let position = self.hand.iter().position(|x| match x {
+            PlayerCard::CityCard(city) => city.city == card,
+            _ => false,
+        });
+        match position {
+            Some(idx) => {
+                other.add_to_hand(self.hand.remove(idx));
+                board
+                    .log
+                    .record(Entry::Shared(self.name.clone(), other.name.clone(), card));
                 true
             }
             None => false,
         }
     }
 
-    pub fn share_knowledge(&mut self, players: &mut [Player]) -> bool {
+    pub fn take(&mut self, board: &mut Board, other: &mut Player, card: Cities) -> bool {
+        other.give(board, self, card)
+    }
+
+    pub fn share_knowledge(&mut self, board: &mut Board, players: &mut [Player]) -> bool {
         let mut options = Vec::new();
         let mut actions = Vec::new();
         for (i, player) in players.iter().enumerate() {
             if self.location == player.location {
-                for (j, card) in self.hand.iter().enumerate() {
-                    match card {
-                        PlayerCard::CityCard(city) => {
-                            if self.role == Role::Researcher || city.city == self.location {
-                                options.push(format!(
-                                    "Give {} to {} | {}/{} cards",
-                                    card,
-                                    player.name(),
-                                    player.hand.len(),
-                                    MAX_CARDS_IN_HAND
-                                ));
-                                actions.push((players.len(), i, j));
-                            }
+                for card in &self.hand {
+                    if let PlayerCard::CityCard(city) = card {
+                        if self.can_give(player, city.city) {
+                            options.push(tr!(
+                                "share.give",
+                                card,
+                                player.name(),
+                                player.hand.len(),
+                                MAX_CARDS_IN_HAND
+                            ));
+                            actions.push((i, city.city, true));
                         }
-                        _ => (),
                     }
                 }
-                for (j, card) in player.hand.iter().enumerate() {
-                    match card {
-                        PlayerCard::CityCard(city) => {
-                            if player.role == Role::Researcher || city.city == self.location {
-                                options.push(format!(
-                                    "Take {} from {} | {}/{} cards",
-                                    card,
-                                    player.name(),
-                                    player.hand.len(),
-                                    MAX_CARDS_IN_HAND
-                                ));
-                                actions.push((i, players.len(), j));
-                            }
+                for card in &player.hand {
+                    if let PlayerCard::CityCard(city) = card {
+                        if player.can_give(self, city.city) {
+                            options.push(tr!(
+                                "share.take",
+                                card,
+                                player.name(),
+                                player.hand.len(),
+                                MAX_CARDS_IN_HAND
+                            ));
+                            actions.push((i, city.city, false));
                         }
-                        _ => (),
                     }
                 }
             }
         }
         let selection = menu_cancelable(
-            format!(
-                "{}'s Share Knowledge Menu | {}/{} cards",
+            &tr!(
+                "menu.share",
                 self.name(),
                 self.hand.len(),
                 MAX_CARDS_IN_HAND
-            )
-            .as_str(),
+            ),
             &options,
         );
         if selection == 0 {
             return false;
         }
-        let (from_idx, to_idx, card_idx) = actions[selection - 1];
-        if from_idx == to_idx {
+        let (idx, card, giving) = actions[selection - 1];
+        self.share(board, &mut players[idx], card, giving)
+    }
+
+    /// Gives `card` to `other`, or takes it from them, once `other` agrees. Whoever receives the
+    /// card has to get back down to the hand limit straight away.
+    pub fn share(
+        &mut self,
+        board: &mut Board,
+        other: &mut Player,
+        card: Cities,
+        giving: bool,
+    ) -> bool {
+        let legal = if giving {
+            self.can_give(other, card)
+        } else {
+            other.can_give(self, card)
+        };
+        if !legal {
             return false;
         }
-        if from_idx == players.len() {
-            players[to_idx].add_to_hand(self.hand.remove(card_idx));
-        } else if to_idx == players.len() {
-            self.add_to_hand(players[from_idx].hand.remove(card_idx));
+        let title = if giving {
+            tr!("menu.share_accept", other.name(), card, self.name())
         } else {
+            tr!("menu.share_hand_over", other.name(), card, self.name())
+        };
+        if menu(&title, &[tr!("share.agree"), tr!("share.refuse")]) != 1 {
+            say!("{}", tr!("share.refused", other.name()));
             return false;
         }
+        if giving {
+            let shared = self.give(board, other, card);
+            if shared {
+                other.enforce_hand_limit(board);
+            }
+            shared
+        } else {
+            let shared = self.take(board, other, card);
+            if shared {
+                self.enforce_hand_limit(board);
+            }
+            shared
+        }
+    }
+
+    /// Has the player discard cards or play Event cards until they hold no more than
+    /// `MAX_CARDS_IN_HAND`.
+    pub fn enforce_hand_limit(&mut self, board: &mut Board) {
+        while self.hand.len() > MAX_CARDS_IN_HAND {
+            let mut options = Vec::new();
+            let mut choices = Vec::new();
+            for (idx, card) in self.hand.iter().enumerate() {
+                if let PlayerCard::EventCard(event) = card {
+                    options.push(tr!("hand.play", event));
+                    choices.push((idx, true));
+                }
+                options.push(tr!("hand.discard", card));
+                choices.push((idx, false));
+            }
+            let selection = menu(
+                &tr!(
+                    "menu.discard",
+                    self.name(),
+                    self.hand.len(),
+                    MAX_CARDS_IN_HAND
+                ),
+                &options,
+            );
+            let (idx, play) = choices[selection - 1];
+            match self.hand.remove(idx) {
+                PlayerCard::EventCard(event) if play => {
+                    self.play_event(board, event);
+                }
+                card => {
+                    board.log.record(Entry::Discarded(self.name.clone(), card));
+                    board.player_discard.discard_to_top(card);
+                }
+            }
+        }
+    }
+
+    /// Whether this player could cure `color` right here.
+    pub fn can_cure(&self, board: &Board, color: Color) -> bool {
+        board.disease_state(color) == DiseaseState::Default
+            && board.research_stations().contains(&self.location)
+            && self.cards_of_color(color).len() >= self.cards_to_cure()
+    }
+
+    /// Discards the City cards needed and cures `color`, if standing at a research station.
+    pub fn cure(&mut self, board: &mut Board, color: Color) -> bool {
+        if !self.can_cure(board, color) {
+            return false;
+        }
+        let cards = self.cards_of_color(color);
+        for card in cards.into_iter().take(self.cards_to_cure()) {
+            self.discard_city_card(board, card);
+        }
+        board.log.record(Entry::Cured(self.name.clone(), color));
+        if board.total_cubes(color) == 0 {
+            board.set_disease_state(color, DiseaseState::Eradicated);
+            board.log.record(Entry::Eradicated(color));
+        } else {
+            board.set_disease_state(color, DiseaseState::Cured);
+        }
+        say!("{}", tr!("cure.discovered", self.name(), color));
         true
     }
 
     pub fn discover_cure(&mut self, board: &mut Board) -> bool {
-        const MIN_CARDS_TO_CURE: u8 = 5;
-        let city = board.map.get(&self.location).unwrap();
-        true
+        if !board.research_stations().contains(&self.location) {
+            println!("{}", tr!("cure.no_station", self.name(), self.location));
+            return false;
+        }
+        let colors = [Color::Blue, Color::Yellow, Color::Black, Color::Red];
+        let options: Vec<String> = colors
+            .iter()
+            .map(|color| {
+                tr!(
+                    "cure.option",
+                    color,
+                    self.cards_of_color(*color).len(),
+                    self.cards_to_cure(),
+                    board.disease_state(*color)
+                )
+            })
+            .collect();
+        let selection = menu_cancelable(&tr!("menu.cure", self.name(), self.location), &options);
+        selection != 0 && self.cure(board, colors[selection - 1])
     }
 }
 
@@ -491,20 +932,118 @@ impl std::fmt::Display for Player {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(
             f,
-            "{} ({}) in {}\nwith",
-            self.name(),
-            self.role,
-            self.location
+            "{}",
+            tr!("player.summary", self.name(), self.role, self.location)
         )?;
 
         if self.hand.is_empty() {
-            writeln!(f, "\tnothing")?;
+            writeln!(f, "\t{}", tr!("player.empty_hand"))?;
         } else {
             for card in &self.hand {
                 writeln!(f, "\t{}", &card)?;
             }
         }
 
-        write!(f, "{}", "in hand.")
+        write!(f, "{}", tr!("player.in_hand"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLUE: [Cities; 5] = [
+        Cities::Atlanta,
+        Cities::Chicago,
+        Cities::Montreal,
+        Cities::London,
+        Cities::Madrid,
+    ];
+
+    #[test]
+    fn cures_at_a_research_station_with_enough_cards() {
+        let mut board = Board::new();
+        board.infect_city(Cities::Paris);
+        let mut ana = Player::new("Ana", Role::Medic).holding(&BLUE);
+
+        assert!(ana.cure(&mut board, Color::Blue));
+        assert_eq!(board.disease_state(Color::Blue), DiseaseState::Cured);
+        assert!(ana.cards_of_color(Color::Blue).is_empty());
+        assert_eq!(board.player_discard.len(), BLUE.len());
+        assert!(!ana.cure(&mut board, Color::Blue));
+    }
+
+    #[test]
+    fn cannot_cure_away_from_a_station_or_short_of_cards() {
+        let mut board = Board::new();
+        let mut away = Player::new("Ana", Role::Medic)
+            .at(Cities::Chicago)
+            .holding(&BLUE);
+        assert!(!away.cure(&mut board, Color::Blue));
+        let mut short = Player::new("Bo", Role::Dispatcher).holding(&BLUE[..4]);
+        assert!(!short.cure(&mut board, Color::Blue));
+        // The Scientist needs one card fewer.
+        let mut scientist = Player::new("Cy", Role::Scientist).holding(&BLUE[..4]);
+        assert!(scientist.cure(&mut board, Color::Blue));
+        assert_eq!(board.disease_state(Color::Blue), DiseaseState::Eradicated);
+    }
+
+    #[test]
+    fn a_cure_with_no_cubes_left_eradicates() {
+        let mut board = Board::new();
+        let mut ana = Player::new("Ana", Role::Medic).holding(&BLUE);
+        assert!(ana.cure(&mut board, Color::Blue));
+        assert_eq!(board.disease_state(Color::Blue), DiseaseState::Eradicated);
+    }
+
+    #[test]
+    fn cubes_spread_to_other_colored_cities_count_against_eradication() {
+        let mut board = Board::new();
+        board.set_disease_state(Color::Blue, DiseaseState::Cured);
+        board
+            .map
+            .get_mut(&Cities::Cairo)
+            .unwrap()
+            .set_infection_count(Color::Blue, 2);
+        board.infect_city(Cities::Atlanta);
+        assert_eq!(board.total_cubes(Color::Blue), 3);
+
+        let mut ana = Player::new("Ana", Role::Dispatcher);
+        assert!(ana.treat(&mut board, Color::Blue));
+        assert_eq!(board.total_cubes(Color::Blue), 2);
+        assert_eq!(board.disease_state(Color::Blue), DiseaseState::Cured);
+
+        // Treating a cured disease takes every cube of it from the city.
+        let mut bo = Player::new("Bo", Role::Dispatcher).at(Cities::Cairo);
+        assert!(bo.treat(&mut board, Color::Blue));
+        assert_eq!(board.total_cubes(Color::Blue), 0);
+        assert_eq!(board.disease_state(Color::Blue), DiseaseState::Eradicated);
+        assert!(!bo.treat(&mut board, Color::Blue));
+    }
+
+    #[test]
+    fn treating_an_uncured_disease_takes_one_cube() {
+        let mut board = Board::new();
+        board.infect_city(Cities::Atlanta);
+        board.infect_city(Cities::Atlanta);
+        let mut ana = Player::new("Ana", Role::Dispatcher);
+        assert!(ana.treat(&mut board, Color::Blue));
+        assert_eq!(board.total_cubes(Color::Blue), 1);
+        assert_eq!(board.disease_state(Color::Blue), DiseaseState::Default);
+    }
+
+    #[test]
+    fn all_four_cures_win() {
+        let mut board = Board::new();
+        for (i, color) in Color::ALL.into_iter().enumerate() {
+            assert!(!board.all_cured());
+            let state = if i % 2 == 0 {
+                DiseaseState::Cured
+            } else {
+                DiseaseState::Eradicated
+            };
+            board.set_disease_state(color, state);
+        }
+        assert!(board.all_cured());
     }
 }
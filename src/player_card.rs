@@ -2,12 +2,20 @@ use std::collections::VecDeque;
 
 use rand::seq::SliceRandom;
 
-use crate::{common::Color, deck::Deck, board::Cities, player::Player};
+use crate::{
+    board::Cities,
+    common::Color,
+    deck::Deck,
+    epidemic_timing::Pile,
+    i18n::{text, text_or, tr},
+    player::Player,
+    random::with_rng,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct City {
     pub(crate) city: Cities,
-    color: Color,
+    pub(crate) color: Color,
     country: &'static str,
     flag: &'static str,
     pub(crate) population: u32,
@@ -16,27 +24,7 @@ pub struct City {
 
 impl PartialOrd for City {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        match self.color.partial_cmp(&other.color) {
-            Some(core::cmp::Ordering::Equal) => {}
-            ord => return ord,
-        }
-        match self.city.partial_cmp(&other.city) {
-            Some(core::cmp::Ordering::Equal) => {}
-            ord => return ord,
-        }
-        match self.population.partial_cmp(&other.population) {
-            Some(core::cmp::Ordering::Equal) => {}
-            ord => return ord,
-        }
-        match self.country.partial_cmp(&other.country) {
-            Some(core::cmp::Ordering::Equal) => {}
-            ord => return ord,
-        }
-        match self.flag.partial_cmp(&other.flag) {
-            Some(core::cmp::Ordering::Equal) => {}
-            ord => return ord,
-        }
-        self.population_density.partial_cmp(&other.population_density)
+        Some(self.cmp(other))
     }
 }
 
@@ -60,12 +48,36 @@ pub enum Events {
     ResilientPopulation,
 }
 
+impl Events {
+    pub const ALL: [Events; 5] = [
+        Events::Airlift,
+        Events::Forecast,
+        Events::GovernmentGrant,
+        Events::OneQuietNight,
+        Events::ResilientPopulation,
+    ];
+
+    pub fn description(&self) -> &'static str {
+        text(&format!("event.{:?}.description", self))
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Event {
     event: Events,
-    description: &'static str,
 }
 
+impl Event {
+    pub fn event(&self) -> Events {
+        self.event
+    }
+
+    pub fn description(&self) -> &'static str {
+        self.event.description()
+    }
+}
+
+#[allow(clippy::enum_variant_names)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum PlayerCard {
     CityCard(City),
@@ -75,7 +87,7 @@ pub enum PlayerCard {
 
 impl std::fmt::Display for Event {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{} | {}", self.event, self.description)
+        write!(f, "{} | {}", self.event, self.description())
     }
 }
 
@@ -84,7 +96,11 @@ impl std::fmt::Display for City {
         write!(
             f,
             "{} | {} {} | 👥 {} | 👤/◼️ {}/km²",
-            self.city, self.flag, self.country, self.population, self.population_density
+            self.city,
+            self.flag,
+            text_or(&format!("country.{}", self.country), self.country),
+            self.population,
+            self.population_density
         )
     }
 }
@@ -92,9 +108,9 @@ impl std::fmt::Display for City {
 impl std::fmt::Display for PlayerCard {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::CityCard(city) => write!(f, "City: {}", city),
-            Self::EpidemicCard => write!(f, "{}", "Epidemic!"),
-            Self::EventCard(event) => write!(f, "Event: {}", event),
+            Self::CityCard(city) => write!(f, "{}", tr!("card.city", city)),
+            Self::EpidemicCard => write!(f, "{}", tr!("card.epidemic")),
+            Self::EventCard(event) => write!(f, "{}", tr!("card.event", event)),
         }
     }
 }
@@ -104,29 +120,30 @@ impl Deck<PlayerCard> {
         Self(VecDeque::from(make_deck()))
     }
 
-    pub fn add_epidemic_cards(&mut self, epidemics: u8) {
-        let chunk_size = (self.0.len() as f64 / epidemics as f64).round() as usize;
-        let mut old_deck = Vec::from(self.0.clone());
-        let mut new_deck = VecDeque::new();
-        let mut count = 0;
-        for chunk in old_deck.chunks_mut(chunk_size) {
-            let mut chunk = chunk.to_vec();
-            if count < epidemics {
-                chunk.push(PlayerCard::EpidemicCard);
-            }
-            count += 1;
-            println!("Chunk {} :: {:?}", count, chunk);
-            chunk.shuffle(&mut rand::thread_rng());
-            new_deck.append(&mut VecDeque::from(chunk));
+    /// Splits the deck into one pile per Epidemic, as even as they can be with the bigger
+    /// piles at the bottom, shuffles an Epidemic card into each and stacks them back up.
+    pub fn add_epidemic_cards(&mut self, epidemics: u8) -> Vec<Pile> {
+        let mut cards = Vec::from(std::mem::take(&mut self.0));
+        let mut piles = Vec::new();
+        for count in (1..=epidemics as usize).rev() {
+            let mut pile: Vec<PlayerCard> = cards.drain(..cards.len().div_ceil(count)).collect();
+            pile.push(PlayerCard::EpidemicCard);
+            piles.push(Pile {
+                size: pile.len(),
+                has_epidemic: true,
+            });
+            with_rng(|rng| pile.shuffle(rng));
+            self.0.extend(pile);
         }
-        self.0 = new_deck;
+        // Without Epidemics, the deck stays as it was.
+        self.0.extend(cards);
+        piles
     }
 
     pub fn deal(&mut self, players: &mut [Player]) {
         for player in players {
-            match self.draw_from_top() {
-                Some(card) => player.add_to_hand(card),
-                None => (),
+            if let Some(card) = self.draw_from_top() {
+                player.add_to_hand(card)
             }
         }
     }
@@ -557,34 +574,43 @@ fn make_red_cities() -> Vec<PlayerCard> {
 }
 
 fn make_events() -> Vec<PlayerCard> {
-    vec![
-            PlayerCard::EventCard(Event {
-                event: Events::Airlift,
-                description: "Move any 1 pawn to any city. Get permission before moving another player's pawn."
-            }), PlayerCard::EventCard(Event {
-                event: Events::Forecast,
-                description: "Draw, look at, and rearrange the top 6 cards of the Infection Deck. Put them back on top."
-            }), PlayerCard::EventCard(Event {
-                event: Events::GovernmentGrant,
-                description: "Add 1 research station to any city (no City card needed)."
-            }), PlayerCard::EventCard(Event {
-                event: Events::OneQuietNight,
-                description: "Skip the next Infect Cities step (do not flip over any Infection cards)."
-            }), PlayerCard::EventCard(Event {
-                event: Events::ResilientPopulation,
-                description: "Remove any 1 card in the Infection Discard Pile from the game. You may play this between the Infect and Intensify steps of an epidemic."
-            })
-        ]
+    Events::ALL
+        .map(|event| PlayerCard::EventCard(Event { event }))
+        .to_vec()
 }
 
 impl std::fmt::Display for Events {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::Airlift => write!(f, "Airlift"),
-            Self::Forecast => write!(f, "Forecast"),
-            Self::GovernmentGrant => write!(f, "Government Grant"),
-            Self::OneQuietNight => write!(f, "One Quiet Night"),
-            Self::ResilientPopulation => write!(f, "Resilient Population"),
+        write!(f, "{}", text(&format!("event.{:?}", self)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn epidemics_go_one_to_a_pile_with_the_bigger_piles_at_the_bottom() {
+        let mut deck = Deck::<PlayerCard>::new();
+        let cards: Vec<PlayerCard> = (0..45).filter_map(|_| deck.draw_from_top()).collect();
+        let mut deck = Deck(VecDeque::from(cards));
+
+        let piles = deck.add_epidemic_cards(4);
+        let sizes: Vec<usize> = piles.iter().map(|pile| pile.size).collect();
+        assert_eq!(sizes, vec![13, 12, 12, 12]);
+        assert!(piles.iter().all(|pile| pile.has_epidemic));
+        assert_eq!(deck.len(), 49);
+
+        let mut start = 0;
+        for pile in &piles {
+            let epidemics = deck
+                .iter()
+                .skip(start)
+                .take(pile.size)
+                .filter(|card| matches!(card, PlayerCard::EpidemicCard))
+                .count();
+            assert_eq!(epidemics, 1);
+            start += pile.size;
         }
     }
 }
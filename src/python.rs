@@ -0,0 +1,195 @@
+use pyo3::{
+    exceptions::PyValueError,
+    prelude::*,
+    types::{PyDict, PyList},
+};
+
+use crate::{
+    agent, cli,
+    command::{self, Command},
+    env,
+    game::{self, Phase, Seeded},
+    i18n::tr,
+    json::Json,
+    setup::{self, Difficulty},
+};
+
+fn failed(message: String) -> PyErr {
+    PyValueError::new_err(message)
+}
+
+/// The same data the agent mode sends, as dicts, lists and numbers.
+fn to_python<'py>(py: Python<'py>, json: &Json) -> PyResult<Bound<'py, PyAny>> {
+    Ok(match json {
+        Json::Null => py.None().into_bound(py),
+        Json::Bool(value) => value.into_pyobject(py)?.to_owned().into_any(),
+        Json::Number(number) if number.fract() == 0.0 => {
+            (*number as i64).into_pyobject(py)?.into_any()
+        }
+        Json::Number(number) => number.into_pyobject(py)?.into_any(),
+        Json::String(text) => text.into_pyobject(py)?.into_any(),
+        Json::Array(items) => {
+            let items = items
+                .iter()
+                .map(|item| to_python(py, item))
+                .collect::<PyResult<Vec<_>>>()?;
+            PyList::new(py, items)?.into_any()
+        }
+        Json::Object(fields) => {
+            let dict = PyDict::new(py);
+            for (key, value) in fields {
+                dict.set_item(key, to_python(py, value)?)?;
+            }
+            dict.into_any()
+        }
+    })
+}
+
+/// A game driven from Python.
+#[pyclass(name = "Game", unsendable)]
+struct PyGame(Seeded);
+
+impl PyGame {
+    /// Applies `command` from `seat` and returns what the log says happened.
+    fn perform(&mut self, seat: usize, command: &Command) -> PyResult<Vec<String>> {
+        let logged = self.0.game.board.log.entries().len();
+        self.0.apply(seat, command).map_err(failed)?;
+        Ok(self.0.game.board.log.entries()[logged..]
+            .iter()
+            .map(|entry| entry.to_string())
+            .collect())
+    }
+}
+
+#[pymethods]
+impl PyGame {
+    /// `players` are names, each with an optional role after a colon, like `--players`.
+    #[new]
+    #[pyo3(signature = (players, difficulty = "standard", seed = None))]
+    fn new(players: Vec<String>, difficulty: &str, seed: Option<u64>) -> PyResult<Self> {
+        game::set_quiet(true);
+        let players = cli::check_players(players.iter().map(|entry| match entry.split_once(':') {
+            Some((name, role)) => (name.trim(), Some(role.trim())),
+            None => (entry.trim(), None),
+        }))
+        .map_err(failed)?;
+        let difficulty = Difficulty::parse(difficulty).map_err(failed)?;
+        let seed = seed.unwrap_or_else(rand::random);
+        Ok(Self(Seeded::deal(
+            &setup::deal_roles(&players, difficulty),
+            seed,
+        )))
+    }
+
+    /// The seed the game was dealt from.
+    #[getter]
+    fn seed(&self) -> u64 {
+        self.0.seed
+    }
+
+    /// The whole board, every hand and what the game waits for.
+    fn state<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_python(py, &agent::state(&self.0.game))
+    }
+
+    /// The legal actions, numbered the way `apply` picks them.
+    fn legal_actions<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_python(py, &agent::legal_actions(&self.0.game))
+    }
+
+    /// Takes legal action number `action` and returns the log lines it added.
+    fn apply(&mut self, action: usize) -> PyResult<Vec<String>> {
+        let legal = self.0.game.legal_actions();
+        let command = legal
+            .get(action)
+            .ok_or_else(|| failed(tr!("agent.invalid_action", legal.len())))?;
+        let seat = self.0.game.waiting_on().unwrap_or(self.0.game.current);
+        self.perform(seat, command)
+    }
+
+    /// Types `command` like at the prompt, for `seat` or else the player the game waits on.
+    #[pyo3(signature = (command, seat = None))]
+    fn play(&mut self, command: &str, seat: Option<usize>) -> PyResult<Vec<String>> {
+        let command = command::parse(command).map_err(failed)?;
+        let seat = seat.unwrap_or_else(|| self.0.game.waiting_on().unwrap_or(self.0.game.current));
+        if seat >= self.0.game.players.len() {
+            return Err(failed(tr!("agent.invalid_seat")));
+        }
+        self.perform(seat, &command)
+    }
+
+    /// The seat the game waits on, or `None` once it is over.
+    #[getter]
+    fn waiting_on(&self) -> Option<usize> {
+        self.0.game.waiting_on()
+    }
+
+    #[getter]
+    fn over(&self) -> bool {
+        matches!(self.0.game.phase, Phase::Over(_))
+    }
+
+    /// `"victory"`, `"outbreaks"`, `"cubes"` or `"cards"` once the game is over.
+    #[getter]
+    fn outcome(&self) -> Option<String> {
+        match self.0.game.phase {
+            Phase::Over(outcome) => Some(format!("{:?}", outcome).to_lowercase()),
+            _ => None,
+        }
+    }
+}
+
+/// The reinforcement learning environment, with numbered actions and numeric observations.
+#[pyclass(name = "Env", unsendable)]
+struct PyEnv {
+    env: env::Env,
+}
+
+#[pymethods]
+impl PyEnv {
+    #[new]
+    #[pyo3(signature = (players = 4, difficulty = "standard"))]
+    fn new(players: usize, difficulty: &str) -> PyResult<Self> {
+        let difficulty = Difficulty::parse(difficulty).map_err(failed)?;
+        let env = env::Env::new(players, difficulty).map_err(failed)?;
+        Ok(Self { env })
+    }
+
+    #[pyo3(signature = (seed = 0))]
+    fn reset(&mut self, seed: u64) -> Vec<f32> {
+        self.env.reset(seed)
+    }
+
+    /// Returns the observation, the reward and whether the game is over.
+    fn step(&mut self, action: usize) -> PyResult<(Vec<f32>, f32, bool)> {
+        let step = self.env.step(action).map_err(failed)?;
+        Ok((step.observation, step.reward, step.done))
+    }
+
+    fn observation(&self) -> Vec<f32> {
+        self.env.observation()
+    }
+
+    fn action_mask(&self) -> Vec<bool> {
+        self.env.action_mask()
+    }
+
+    /// The command action number `action` stands for right now, as it would be typed.
+    fn describe(&self, action: usize) -> Option<String> {
+        self.env.command(action).map(|command| command.to_string())
+    }
+
+    fn state<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_python(py, &agent::state(self.env.game()))
+    }
+}
+
+/// The engine as a Python module, for notebooks and simulations.
+#[pymodule]
+fn pandemic_cli(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyGame>()?;
+    module.add_class::<PyEnv>()?;
+    module.add("ACTIONS", env::ACTIONS)?;
+    module.add("OBSERVATION_SIZE", env::OBSERVATION_SIZE)?;
+    Ok(())
+}
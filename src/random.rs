@@ -0,0 +1,26 @@
+use std::cell::RefCell;
+
+use rand::{rngs::StdRng, SeedableRng};
+
+thread_local! {
+    /// Every shuffle and random pick in a game draws from this, so a seed replays the same game.
+    static RNG: RefCell<StdRng> = RefCell::new(StdRng::from_entropy());
+}
+
+/// Restarts the random sequence from `seed`.
+pub fn seed(seed: u64) {
+    RNG.with(|rng| *rng.borrow_mut() = StdRng::seed_from_u64(seed));
+}
+
+pub fn with_rng<R>(f: impl FnOnce(&mut StdRng) -> R) -> R {
+    RNG.with(|rng| f(&mut rng.borrow_mut()))
+}
+
+/// Runs `f` drawing from `rng` rather than this thread's generator, so one thread can deal
+/// several games that each replay from their own seed.
+pub fn using<R>(rng: &mut StdRng, f: impl FnOnce() -> R) -> R {
+    RNG.with(|current| std::mem::swap(&mut *current.borrow_mut(), rng));
+    let result = f();
+    RNG.with(|current| std::mem::swap(&mut *current.borrow_mut(), rng));
+    result
+}
@@ -0,0 +1,203 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+};
+
+use crate::{
+    board::{Board, Cities},
+    i18n::tr,
+};
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
+pub enum Movement {
+    DriveFerry,
+    DirectFlight,
+    CharterFlight,
+    ShuttleFlight,
+}
+
+/// A single movement action: where it goes and which City card it discards, if any.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Step {
+    pub(crate) movement: Movement,
+    pub(crate) to: Cities,
+    pub(crate) card: Option<Cities>,
+}
+
+/// Finds the route from `from` to `to` that takes the fewest actions, using the City cards in
+/// `hand` for Direct and Charter Flights. Among routes of equal length the one discarding the
+/// fewest cards wins. Returns `None` if `to` cannot be reached at all.
+pub fn plan_route(board: &Board, from: Cities, hand: &[Cities], to: Cities) -> Option<Vec<Step>> {
+    let stations = board.research_stations();
+    let all_cities = board.all_cities();
+
+    // A search state is a city plus the set of hand cards already spent, as a bitmask.
+    let mut best: HashMap<(Cities, u32), (u32, u32)> = HashMap::new();
+    let mut previous: HashMap<(Cities, u32), ((Cities, u32), Step)> = HashMap::new();
+    let mut queue = BinaryHeap::new();
+
+    best.insert((from, 0), (0, 0));
+    queue.push(Reverse((0, 0, from, 0)));
+
+    while let Some(Reverse((actions, cards, city, used))) = queue.pop() {
+        if best.get(&(city, used)) != Some(&(actions, cards)) {
+            continue;
+        }
+        if city == to {
+            let mut steps = Vec::new();
+            let mut state = (city, used);
+            while let Some((prior, step)) = previous.get(&state) {
+                steps.push(*step);
+                state = *prior;
+            }
+            steps.reverse();
+            return Some(steps);
+        }
+
+        let mut next = Vec::new();
+        for adjacent in board.adjacent_to(city).unwrap_or_default() {
+            next.push((adjacent, used, Movement::DriveFerry, None));
+        }
+        if stations.contains(&city) {
+            for station in &stations {
+                if *station != city {
+                    next.push((*station, used, Movement::ShuttleFlight, None));
+                }
+            }
+        }
+        for (i, card) in hand.iter().enumerate() {
+            if used & (1 << i) != 0 {
+                continue;
+            }
+            if *card == city {
+                for destination in &all_cities {
+                    if *destination != city {
+                        next.push((
+                            *destination,
+                            used | (1 << i),
+                            Movement::CharterFlight,
+                            Some(*card),
+                        ));
+                    }
+                }
+            } else {
+                next.push((*card, used | (1 << i), Movement::DirectFlight, Some(*card)));
+            }
+        }
+
+        for (destination, used_next, movement, card) in next {
+            let cost = (actions + 1, cards + card.is_some() as u32);
+            let state = (destination, used_next);
+            if best.get(&state).is_none_or(|known| cost < *known) {
+                best.insert(state, cost);
+                previous.insert(
+                    state,
+                    (
+                        (city, used),
+                        Step {
+                            movement,
+                            to: destination,
+                            card,
+                        },
+                    ),
+                );
+                queue.push(Reverse((cost.0, cost.1, destination, used_next)));
+            }
+        }
+    }
+    None
+}
+
+impl std::fmt::Display for Movement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DriveFerry => write!(f, "{}", tr!("movement.drive")),
+            Self::DirectFlight => write!(f, "{}", tr!("movement.direct_flight")),
+            Self::CharterFlight => write!(f, "{}", tr!("movement.charter")),
+            Self::ShuttleFlight => write!(f, "{}", tr!("movement.shuttle")),
+        }
+    }
+}
+
+impl std::fmt::Display for Step {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.card {
+            Some(card) => write!(f, "{}", tr!("step.with_card", self.movement, self.to, card)),
+            None => write!(f, "{}", tr!("step", self.movement, self.to)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route(from: Cities, hand: &[Cities], to: Cities) -> Vec<Step> {
+        plan_route(&Board::new(), from, hand, to).unwrap()
+    }
+
+    #[test]
+    fn drives_when_no_card_helps() {
+        let steps = route(Cities::Atlanta, &[], Cities::Lima);
+        assert_eq!(steps.len(), 3);
+        assert!(steps
+            .iter()
+            .all(|step| step.movement == Movement::DriveFerry && step.card.is_none()));
+        assert_eq!(steps.last().unwrap().to, Cities::Lima);
+    }
+
+    #[test]
+    fn flies_direct_with_the_destination_card() {
+        let steps = route(Cities::Atlanta, &[Cities::Tokyo], Cities::Tokyo);
+        assert_eq!(
+            steps,
+            vec![Step {
+                movement: Movement::DirectFlight,
+                to: Cities::Tokyo,
+                card: Some(Cities::Tokyo),
+            }]
+        );
+    }
+
+    #[test]
+    fn charters_with_the_card_of_the_city_it_is_in() {
+        let steps = route(
+            Cities::Atlanta,
+            &[Cities::Paris, Cities::Atlanta],
+            Cities::Lima,
+        );
+        assert_eq!(
+            steps,
+            vec![Step {
+                movement: Movement::CharterFlight,
+                to: Cities::Lima,
+                card: Some(Cities::Atlanta),
+            }]
+        );
+    }
+
+    #[test]
+    fn a_card_spent_on_a_direct_flight_cannot_pay_for_a_charter_flight() {
+        // Flying to Paris and chartering on with the same Paris card would be 2 actions, but
+        // the card is gone after the first flight, so driving is the shortest way.
+        let steps = route(Cities::Atlanta, &[Cities::Paris], Cities::Lima);
+        assert_eq!(steps.len(), 3);
+        assert!(steps.iter().all(|step| step.card.is_none()));
+    }
+
+    #[test]
+    fn each_card_is_used_at_most_once() {
+        let hand = [Cities::Paris, Cities::Sydney];
+        let steps = route(Cities::Atlanta, &hand, Cities::Jakarta);
+        for card in hand {
+            let uses = steps.iter().filter(|step| step.card == Some(card)).count();
+            assert!(uses <= 1, "{:?} used {} times", card, uses);
+        }
+        assert_eq!(steps.last().unwrap().to, Cities::Jakarta);
+    }
+
+    #[test]
+    fn staying_put_takes_no_steps() {
+        assert!(route(Cities::Atlanta, &[Cities::Atlanta], Cities::Atlanta).is_empty());
+    }
+}
@@ -0,0 +1,264 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, BufRead, BufReader, Write},
+    path::Path,
+};
+
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{
+    command::Command,
+    game::{self, Game, Seeded},
+    json, random,
+    role::Role,
+    setup::{Difficulty, Setup},
+};
+
+pub const DEFAULT_PATH: &str = "pandemic-save.txt";
+
+/// Where game `id` of a server is saved: `path` with `-id` before its extension.
+pub fn numbered(path: &str, id: usize) -> String {
+    let path = Path::new(path);
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(extension) => format!("{}-{}.{}", stem, id, extension.to_string_lossy()),
+        None => format!("{}-{}", stem, id),
+    };
+    path.with_file_name(name).to_string_lossy().into_owned()
+}
+
+/// The ids of the games saved next to `path` by `numbered`, lowest first.
+pub fn numbered_ids(path: &str) -> io::Result<Vec<usize>> {
+    let path = Path::new(path);
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let extension = path
+        .extension()
+        .map(|extension| format!(".{}", extension.to_string_lossy()))
+        .unwrap_or_default();
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(error),
+    };
+    let mut ids = Vec::new();
+    for entry in entries {
+        let name = entry?.file_name().to_string_lossy().into_owned();
+        let id = name
+            .strip_prefix(stem.as_ref())
+            .and_then(|rest| rest.strip_prefix('-'))
+            .and_then(|rest| rest.strip_suffix(extension.as_str()))
+            .and_then(|id| id.parse().ok())
+            .filter(|id: &usize| *id > 0 && name == format!("{}-{}{}", stem, id, extension));
+        ids.extend(id);
+    }
+    ids.sort_unstable();
+    Ok(ids)
+}
+
+/// A game as a replay: the setup, the seed it was dealt with and every command applied since,
+/// so it can be rebuilt exactly. Lines are `key = value` like the message catalogs.
+///
+/// Commands are kept in their JSON form, so a save reads back the same in any language.
+pub struct Save {
+    file: File,
+}
+
+impl Save {
+    /// Starts a new save at `path`, replacing whatever was there.
+    pub fn create(path: &str, setup: &Setup, seed: u64) -> io::Result<Self> {
+        let mut file = File::create(path)?;
+        writeln!(file, "# pandemic-cli replay")?;
+        writeln!(file, "seed = {}", seed)?;
+        writeln!(file, "difficulty = {}", setup.difficulty.key())?;
+        for (name, role) in &setup.players {
+            writeln!(file, "player = {}:{}", name, role.key())?;
+        }
+        Ok(Self { file })
+    }
+
+    /// Carries on writing the save at `path`.
+    pub fn append(path: &str) -> io::Result<Self> {
+        Ok(Self {
+            file: OpenOptions::new().append(true).open(path)?,
+        })
+    }
+
+    fn write(&mut self, key: &str, value: String) -> io::Result<()> {
+        writeln!(self.file, "{} = {}", key, value)?;
+        self.file.flush()
+    }
+
+    /// Notes the token that lets the player in `seat` back in.
+    pub fn token(&mut self, seat: usize, token: &str) -> io::Result<()> {
+        self.write("token", format!("{} {}", seat, token))
+    }
+
+    /// Notes a command the game accepted from `seat`.
+    pub fn action(&mut self, seat: usize, command: &Command) -> io::Result<()> {
+        self.write("action", format!("{} {}", seat, command.to_json()))
+    }
+}
+
+/// A game rebuilt from its save.
+pub struct Loaded {
+    pub(crate) seeded: Seeded,
+    pub(crate) tokens: Vec<Option<String>>,
+    pub(crate) difficulty: Difficulty,
+}
+
+fn invalid(line: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("unreadable save line '{}'", line),
+    )
+}
+
+/// Replays the save at `path`. `None` when there is no save there.
+pub fn load(path: &str) -> io::Result<Option<Loaded>> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(error),
+    };
+    let mut seed = None;
+    let mut difficulty = None;
+    let mut players = Vec::new();
+    let mut tokens = Vec::new();
+    let mut actions = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (key, value) = line.split_once(" = ").ok_or_else(|| invalid(&line))?;
+        match key {
+            "seed" => seed = Some(value.parse().map_err(|_| invalid(&line))?),
+            "difficulty" => {
+                difficulty = Some(Difficulty::from_name(value).ok_or_else(|| invalid(&line))?)
+            }
+            "player" => {
+                let (name, role) = value.rsplit_once(':').ok_or_else(|| invalid(&line))?;
+                let role = Role::from_name(role).ok_or_else(|| invalid(&line))?;
+                players.push((name.to_owned(), role));
+            }
+            "token" | "action" => {
+                let (seat, rest) = value.split_once(' ').ok_or_else(|| invalid(&line))?;
+                let seat: usize = seat.parse().map_err(|_| invalid(&line))?;
+                if key == "token" {
+                    tokens.push((seat, rest.to_owned()));
+                } else {
+                    actions.push((seat, rest.to_owned()));
+                }
+            }
+            _ => return Err(invalid(&line)),
+        }
+    }
+    let (Some(seed), Some(difficulty)) = (seed, difficulty) else {
+        return Err(invalid(path));
+    };
+    let setup = Setup {
+        players,
+        difficulty,
+    };
+
+    // The replay is silent: it all happened before.
+    let mut rng = StdRng::seed_from_u64(seed);
+    let replayed = game::quietly(|| {
+        random::using(&mut rng, || {
+            let mut game = Game::new(&setup);
+            actions.iter().try_for_each(|(seat, line)| {
+                let command = json::parse(line)
+                    .ok()
+                    .and_then(|json| Command::from_json(&json))
+                    .ok_or_else(|| invalid(line))?;
+                game.apply(*seat, &command).map_err(|_| invalid(line))
+            })?;
+            Ok::<_, io::Error>(game)
+        })
+    });
+    let game = replayed?;
+
+    let mut seats = vec![None; game.players.len()];
+    for (seat, token) in tokens {
+        if let Some(slot) = seats.get_mut(seat) {
+            *slot = Some(token);
+        }
+    }
+    Ok(Some(Loaded {
+        seeded: Seeded::resumed(game, seed, rng),
+        tokens: seats,
+        difficulty,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{agent, setup};
+
+    #[test]
+    fn every_numbered_save_is_found_past_gaps() {
+        let dir = std::env::temp_dir().join(format!("pandemic-saves-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in [
+            "games-1.txt",
+            "games-3.txt",
+            "games-12.txt",
+            "games-04.txt",
+            "games-x.txt",
+        ] {
+            File::create(dir.join(name)).unwrap();
+        }
+        let path = dir.join("games.txt").to_string_lossy().into_owned();
+        let ids = numbered_ids(&path);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(ids.unwrap(), [1, 3, 12]);
+    }
+
+    #[test]
+    fn a_saved_game_loads_as_it_was_left() {
+        game::set_quiet(true);
+        let path =
+            std::env::temp_dir().join(format!("pandemic-save-test-{}.txt", std::process::id()));
+        let path = path.to_string_lossy().into_owned();
+        let players = [("Ana".to_owned(), None), ("Bo".to_owned(), None)];
+        let setup = setup::deal_roles(&players, Difficulty::Standard);
+        let seed = 7;
+        let mut save = Save::create(&path, &setup, seed).unwrap();
+        save.token(1, "secret").unwrap();
+        let mut rng = StdRng::seed_from_u64(seed);
+        let played = random::using(&mut rng, || {
+            let mut game = Game::new(&setup);
+            let said = Command::Say("¿listos?".to_owned());
+            game.apply(0, &said).unwrap();
+            save.action(0, &said).unwrap();
+            for turn in 0..40 {
+                let Some(seat) = game.waiting_on() else {
+                    break;
+                };
+                let actions = game.legal_actions();
+                let command = actions[turn * 7 % actions.len()].clone();
+                game.apply(seat, &command).unwrap();
+                save.action(seat, &command).unwrap();
+            }
+            game
+        });
+
+        let loaded = load(&path).unwrap().unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            agent::state(&loaded.seeded.game).to_string(),
+            agent::state(&played).to_string()
+        );
+        assert_eq!(
+            loaded.seeded.game.board.log.lines(),
+            played.board.log.lines()
+        );
+        assert_eq!(loaded.tokens, [None, Some("secret".to_owned())]);
+        assert_eq!(loaded.seeded.seed, seed);
+    }
+}
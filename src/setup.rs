@@ -0,0 +1,248 @@
+use std::io::Write;
+
+use crossterm::style::{Color, Stylize};
+use rand::Rng;
+
+use crate::{
+    cli::Options,
+    command::normalize,
+    deck::Deck,
+    i18n::{all_texts, tr},
+    menu::{menu, menu_injectable},
+    random::with_rng,
+    role::{Role, RoleCard},
+    theme::Themed,
+};
+
+pub const MIN_PLAYERS: usize = 2;
+pub const MAX_PLAYERS: usize = 4;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Difficulty {
+    Introductory,
+    Standard,
+    Heroic,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [
+        Difficulty::Introductory,
+        Difficulty::Standard,
+        Difficulty::Heroic,
+    ];
+
+    pub fn epidemics(&self) -> u8 {
+        match self {
+            Self::Introductory => 4,
+            Self::Standard => 5,
+            Self::Heroic => 6,
+        }
+    }
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::Introductory => "introductory",
+            Self::Standard => "standard",
+            Self::Heroic => "heroic",
+        }
+    }
+
+    /// Matches the key or the first word of the name in any language, e.g. `heroic` or
+    /// `heroica`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let wanted = normalize(name);
+        Self::ALL.into_iter().find(|difficulty| {
+            difficulty.key() == wanted
+                || all_texts(&format!("difficulty.{}", difficulty.key()))
+                    .into_iter()
+                    .any(|text| normalize(text).split(' ').next() == Some(wanted.as_str()))
+        })
+    }
+
+    /// Like `from_name`, explaining what the names are when `name` is none of them.
+    pub fn parse(name: &str) -> Result<Self, String> {
+        Self::from_name(name).ok_or_else(|| {
+            let names: Vec<&str> = Self::ALL.iter().map(|d| d.key()).collect();
+            tr!("cli.unknown_difficulty", name, names.join(", "))
+        })
+    }
+}
+
+impl std::fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", tr!(&format!("difficulty.{}", self.key())))
+    }
+}
+
+/// Everything decided before the cards are dealt.
+#[derive(Debug)]
+pub struct Setup {
+    pub(crate) players: Vec<(String, Role)>,
+    pub(crate) difficulty: Difficulty,
+}
+
+/// How many cards each player starts with: 4 for 2 players, 3 for 3 and 2 for 4.
+pub fn cards_per_player(players: usize) -> usize {
+    6 - players.clamp(MIN_PLAYERS, MAX_PLAYERS)
+}
+
+fn error(message: String) {
+    println!(
+        "{}: {}",
+        tr!("error").with(Color::Red).bold().themed(),
+        message
+    );
+}
+
+/// Prints `prompt` and reads a trimmed line, leaving the game if input has run out.
+fn read_line(prompt: &str) -> String {
+    print!("{} ", prompt);
+    std::io::stdout().flush().unwrap_or_default();
+    let mut input = String::new();
+    match std::io::stdin().read_line(&mut input) {
+        Ok(0) | Err(_) => {
+            println!();
+            println!("{}", tr!("setup.input_closed"));
+            std::process::exit(1);
+        }
+        Ok(_) => input.trim().to_owned(),
+    }
+}
+
+fn prompt_player_count() -> usize {
+    loop {
+        match read_line(&tr!("setup.player_count")).parse::<usize>() {
+            Ok(count) if (MIN_PLAYERS..=MAX_PLAYERS).contains(&count) => return count,
+            _ => error(tr!("setup.player_count_error", MIN_PLAYERS, MAX_PLAYERS)),
+        }
+    }
+}
+
+/// Rejects empty names and names already taken, ignoring case.
+pub fn check_name(name: &str, taken: &[String]) -> Result<(), String> {
+    if name.is_empty() {
+        Err(tr!("setup.name_empty"))
+    } else if taken
+        .iter()
+        .any(|other| other.to_lowercase() == name.to_lowercase())
+    {
+        Err(tr!("setup.name_taken", name))
+    } else {
+        Ok(())
+    }
+}
+
+fn prompt_name(number: usize, taken: &[String]) -> String {
+    loop {
+        let name = read_line(&tr!("setup.player_name", number));
+        match check_name(&name, taken) {
+            Ok(()) => return name,
+            Err(message) => error(message),
+        }
+    }
+}
+
+fn prompt_names() -> Vec<String> {
+    let count = prompt_player_count();
+    let mut names: Vec<String> = Vec::new();
+    for number in 1..=count {
+        let name = prompt_name(number, &names);
+        names.push(name);
+    }
+    names
+}
+
+/// Lets the player pick one of the roles nobody has yet, or leave it to chance.
+fn prompt_role(name: &str, available: &mut Vec<RoleCard>) -> Role {
+    let options: Vec<String> = available
+        .iter()
+        .map(|card| format!("{} | {}", card.role, card.description.join(" ")))
+        .collect();
+    let selection = menu_injectable(&tr!("menu.role", name), &tr!("setup.random_role"), &options);
+    let idx = if selection == 0 {
+        with_rng(|rng| rng.gen_range(0..available.len()))
+    } else {
+        selection - 1
+    };
+    available.remove(idx).role
+}
+
+/// Walks through player count, names, roles and difficulty, asking only for what `options`
+/// leaves out. Anything that was asked for is shown for review before the cards are dealt, and
+/// starting over forgets those answers.
+pub fn prompt_setup(options: &Options) -> Setup {
+    loop {
+        let named: Vec<(String, Option<Role>)> = match &options.players {
+            Some(players) => players.clone(),
+            None => prompt_names()
+                .into_iter()
+                .map(|name| (name, None))
+                .collect(),
+        };
+
+        let mut available: Vec<RoleCard> = Deck::<RoleCard>::new()
+            .0
+            .into_iter()
+            .filter(|card| !named.iter().any(|(_, role)| *role == Some(card.role)))
+            .collect();
+        let prompted = options.players.is_none()
+            || named.iter().any(|(_, role)| role.is_none())
+            || options.difficulty.is_none();
+        let players: Vec<(String, Role)> = named
+            .into_iter()
+            .map(|(name, role)| {
+                let role = role.unwrap_or_else(|| prompt_role(&name, &mut available));
+                (name, role)
+            })
+            .collect();
+
+        let difficulty = options.difficulty.unwrap_or_else(|| {
+            Difficulty::ALL[menu(&tr!("menu.difficulty"), &Difficulty::ALL) - 1]
+        });
+
+        let setup = Setup {
+            players,
+            difficulty,
+        };
+        if !prompted || review(&setup) {
+            return setup;
+        }
+    }
+}
+
+fn review(setup: &Setup) -> bool {
+    println!("{:=^94}", format!(" {} ", tr!("menu.review")));
+    for (i, (name, role)) in setup.players.iter().enumerate() {
+        println!("\t{}", tr!("setup.review_player", i + 1, name, role));
+    }
+    println!("\t{}", tr!("setup.review_difficulty", setup.difficulty));
+    menu(
+        &tr!("menu.review_confirm"),
+        &[tr!("setup.start"), tr!("setup.start_over")],
+    ) == 1
+}
+
+/// The setup for a game nobody is asked about, such as one played by bots. Players named
+/// without a role are dealt one at random.
+pub fn deal_roles(named: &[(String, Option<Role>)], difficulty: Difficulty) -> Setup {
+    let mut available: Vec<RoleCard> = Deck::<RoleCard>::new()
+        .0
+        .into_iter()
+        .filter(|card| !named.iter().any(|(_, role)| *role == Some(card.role)))
+        .collect();
+    let players = named
+        .iter()
+        .map(|(name, role)| {
+            let role = role.unwrap_or_else(|| {
+                available
+                    .remove(with_rng(|rng| rng.gen_range(0..available.len())))
+                    .role
+            });
+            (name.clone(), role)
+        })
+        .collect();
+    Setup {
+        players,
+        difficulty,
+    }
+}
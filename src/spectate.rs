@@ -0,0 +1,177 @@
+use std::{
+    io::{self, Read, Write},
+    net::{Shutdown, TcpListener, TcpStream},
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use crate::{
+    board::Board,
+    i18n::tr,
+    net::{with_port, WRITE_TIMEOUT},
+    player::Player,
+    theme::Theme,
+    tui::render_world_map,
+};
+
+pub const DEFAULT_PORT: u16 = 7778;
+
+/// An address with a slash in it is a Unix domain socket, anything else a TCP address.
+fn is_socket_path(addr: &str) -> bool {
+    addr.contains('/')
+}
+
+/// Adds the default spectator port to a TCP address given without one.
+pub fn address(addr: &str) -> String {
+    if is_socket_path(addr) {
+        addr.to_owned()
+    } else {
+        with_port(addr, DEFAULT_PORT)
+    }
+}
+
+#[derive(Default)]
+struct Watchers {
+    streams: Vec<Box<dyn Write + Send>>,
+    /// The latest snapshot, so whoever starts watching mid-game sees the board straight away.
+    last: String,
+}
+
+impl Watchers {
+    fn join(&mut self, mut stream: Box<dyn Write + Send>) {
+        if stream.write_all(self.last.as_bytes()).is_ok() {
+            self.streams.push(stream);
+        }
+    }
+
+    /// Sends `text` to everyone watching and forgets those who stopped. A watcher who stops
+    /// reading is given up after `WRITE_TIMEOUT`, so the game never waits on them for long.
+    fn send(&mut self, text: &str) {
+        self.streams
+            .retain_mut(|stream| stream.write_all(text.as_bytes()).is_ok());
+    }
+}
+
+/// A read-only view of the game for other screens: every update redraws the map and lists
+/// what happened since the last one. Nothing is ever read from the watchers, so they cannot
+/// take part.
+pub struct Feed {
+    watchers: Arc<Mutex<Watchers>>,
+    /// How many log entries have already been shown.
+    logged: usize,
+    /// The Unix socket to remove once the game is over.
+    path: Option<String>,
+}
+
+impl Feed {
+    pub fn open(addr: &str) -> io::Result<Self> {
+        let watchers: Arc<Mutex<Watchers>> = Arc::default();
+        let shared = Arc::clone(&watchers);
+        let mut path = None;
+        if is_socket_path(addr) {
+            #[cfg(unix)]
+            {
+                use std::os::unix::{fs::FileTypeExt, net::UnixListener};
+                // A socket left behind by an earlier game would stop this one from binding.
+                if std::fs::metadata(addr).is_ok_and(|meta| meta.file_type().is_socket()) {
+                    std::fs::remove_file(addr)?;
+                }
+                let listener = UnixListener::bind(addr)?;
+                path = Some(addr.to_owned());
+                thread::spawn(move || {
+                    for stream in listener.incoming().flatten() {
+                        let _ = stream.shutdown(Shutdown::Read);
+                        if stream.set_write_timeout(Some(WRITE_TIMEOUT)).is_ok() {
+                            shared.lock().unwrap().join(Box::new(stream));
+                        }
+                    }
+                });
+            }
+            #[cfg(not(unix))]
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                tr!("spectate.no_unix_sockets"),
+            ));
+        } else {
+            let listener = TcpListener::bind(addr)?;
+            thread::spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    let _ = stream.shutdown(Shutdown::Read);
+                    if stream.set_write_timeout(Some(WRITE_TIMEOUT)).is_ok() {
+                        shared.lock().unwrap().join(Box::new(stream));
+                    }
+                }
+            });
+        }
+        println!("{}", tr!("spectate.open", addr));
+        Ok(Self {
+            watchers,
+            logged: 0,
+            path,
+        })
+    }
+
+    /// Starts over with another game, whose log is shown from its first entry.
+    pub fn restart(&mut self) {
+        self.logged = 0;
+    }
+
+    /// Redraws the board for the watchers, followed by the log entries since the last update.
+    pub fn update(&mut self, board: &Board, players: &[&Player]) {
+        let mut text = String::new();
+        if Theme::current().uses_color() {
+            // Clear the screen and start at the top, so a projector shows one board at a time.
+            text.push_str("\x1b[2J\x1b[H");
+        } else {
+            text.push_str(&format!("{:=^94}\n", ""));
+        }
+        text.push_str(&render_world_map(board, players));
+        let entries = &board.log.entries()[self.logged.min(board.log.entries().len())..];
+        self.logged += entries.len();
+        for entry in entries {
+            text.push_str(&format!("  {}\n", entry));
+        }
+        let mut watchers = self.watchers.lock().unwrap();
+        watchers.send(&text);
+        watchers.last = text;
+    }
+
+    /// A line for the watchers below the latest board, such as how the game ended.
+    pub fn announce(&mut self, line: &str) {
+        let mut watchers = self.watchers.lock().unwrap();
+        let line = format!("{}\n", line);
+        watchers.send(&line);
+        watchers.last.push_str(&line);
+    }
+}
+
+impl Drop for Feed {
+    fn drop(&mut self) {
+        if let Some(path) = &self.path {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// Shows the feed of a game being played elsewhere until it ends.
+pub fn watch(addr: &str) -> io::Result<()> {
+    let mut stream: Box<dyn Read> = if is_socket_path(addr) {
+        #[cfg(unix)]
+        {
+            Box::new(std::os::unix::net::UnixStream::connect(addr)?)
+        }
+        #[cfg(not(unix))]
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            tr!("spectate.no_unix_sockets"),
+        ));
+    } else {
+        let stream = TcpStream::connect(addr)?;
+        stream.shutdown(Shutdown::Write)?;
+        Box::new(stream)
+    };
+    println!("{}", tr!("net.connected", addr));
+    io::copy(&mut stream, &mut io::stdout())?;
+    println!("{}", tr!("net.disconnected"));
+    Ok(())
+}
@@ -0,0 +1,185 @@
+use crossterm::style::{Color as ConsoleColor, Stylize};
+
+use crate::{
+    board::{self, Board, Cities},
+    common::Color,
+    i18n::tr,
+    player::Player,
+    theme::Themed,
+};
+
+/// Prints everything public on the table: diseases, outbreaks, infection rate, infected cities,
+/// research stations, every player and the infection discard pile. The order of the Player Deck
+/// stays hidden.
+pub fn show_status(board: &Board, player: &Player, others: &[Player]) {
+    print!(
+        "{}",
+        Status {
+            board,
+            player,
+            others
+        }
+    );
+}
+
+/// How much of the table talk the status screen shows.
+const CHAT_LINES: usize = 5;
+
+/// The status screen as text, so it can be sent to players at other terminals too.
+pub struct Status<'a> {
+    pub(crate) board: &'a Board,
+    pub(crate) player: &'a Player,
+    pub(crate) others: &'a [Player],
+}
+
+impl std::fmt::Display for Status<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (board, player, others) = (self.board, self.player, self.others);
+        writeln!(f, "{:=^94}", format!(" {} ", tr!("status.title")))?;
+        let outbreaks = format!("{}/{}", board.outbreaks, board::MAX_OUTBREAKS);
+        writeln!(
+            f,
+            "{}",
+            tr!(
+                "status.outbreaks",
+                if board.outbreaks + 2 >= board::MAX_OUTBREAKS {
+                    outbreaks
+                        .with(ConsoleColor::Red)
+                        .bold()
+                        .themed()
+                        .to_string()
+                } else {
+                    outbreaks
+                }
+            )
+        )?;
+        let track: Vec<String> = board::INFECTION_RATE
+            .iter()
+            .enumerate()
+            .map(|(i, rate)| {
+                if i == board.infection_rate_position() {
+                    format!("[{}]", rate)
+                } else {
+                    rate.to_string()
+                }
+            })
+            .collect();
+        writeln!(f, "{}", tr!("status.infection_rate", track.join(" ")))?;
+        writeln!(
+            f,
+            "{}",
+            tr!(
+                "status.epidemics",
+                board.epidemics,
+                board.epidemics_remaining(),
+                board.player_deck.len()
+            )
+        )?;
+
+        writeln!(f, "{}", tr!("status.diseases"))?;
+        for color in [Color::Blue, Color::Yellow, Color::Black, Color::Red] {
+            writeln!(
+                f,
+                "\t{}",
+                tr!(
+                    "status.disease",
+                    color,
+                    board::MAX_INFECTION_PER_TYPE - board.total_cubes(color),
+                    board::MAX_INFECTION_PER_TYPE,
+                    board.disease_state(color)
+                )
+            )?;
+        }
+
+        // The most cubes of a single color decides how close a city is to an outbreak.
+        let mut infected: Vec<(Cities, u8, u8)> = board
+            .map
+            .iter()
+            .map(|(city, city_obj)| {
+                let counts = [Color::Blue, Color::Yellow, Color::Black, Color::Red]
+                    .map(|color| city_obj.infection_count(color));
+                (
+                    *city,
+                    counts.into_iter().max().unwrap_or(0),
+                    counts.into_iter().sum(),
+                )
+            })
+            .filter(|(_, _, total)| *total > 0)
+            .collect();
+        infected.sort_by(|a, b| b.1.cmp(&a.1).then(b.2.cmp(&a.2)).then(a.0.cmp(&b.0)));
+        writeln!(f, "{}", tr!("status.infected"))?;
+        if infected.is_empty() {
+            writeln!(f, "\t{}", tr!("status.none"))?;
+        }
+        for (city, most, _) in infected {
+            let city_obj = board.map.get(&city).unwrap();
+            let cubes: Vec<String> = [Color::Blue, Color::Yellow, Color::Black, Color::Red]
+                .into_iter()
+                .filter(|color| city_obj.infection_count(*color) > 0)
+                .map(|color| format!("{} {}", city_obj.infection_count(color), color))
+                .collect();
+            writeln!(
+                f,
+                "\t{}: {}{}",
+                city,
+                cubes.join(", "),
+                if most >= board::MAX_INFECTION_PER_TYPE_PER_CITY {
+                    format!(
+                        " {}",
+                        tr!("status.outbreak_next").with(ConsoleColor::Red).themed()
+                    )
+                } else {
+                    String::new()
+                }
+            )?;
+        }
+
+        let stations: Vec<String> = board
+            .research_stations()
+            .iter()
+            .map(|city| city.to_string())
+            .collect();
+        writeln!(
+            f,
+            "{}",
+            tr!(
+                "status.stations",
+                stations.len(),
+                board::MAX_RESEARCH_STATIONS,
+                stations.join(", ")
+            )
+        )?;
+
+        writeln!(f, "{}", tr!("status.players"))?;
+        for member in std::iter::once(player).chain(others.iter()) {
+            writeln!(f, "{}", member)?;
+        }
+
+        let chat = board.log.chat(CHAT_LINES);
+        if !chat.is_empty() {
+            writeln!(f, "{}", tr!("status.chat"))?;
+            for (player, text) in chat {
+                writeln!(f, "\t{}: {}", player.bold().themed(), text)?;
+            }
+        }
+        let notes = board.log.notes();
+        if !notes.is_empty() {
+            writeln!(f, "{}", tr!("status.notes"))?;
+            for (pin, player, text) in notes {
+                writeln!(f, "\t{}", tr!("status.note", pin, text, player))?;
+            }
+        }
+
+        let discarded: Vec<String> = board
+            .infection_discard
+            .iter()
+            .rev()
+            .map(|card| card.city.to_string())
+            .collect();
+        writeln!(f, "{}", tr!("status.infection_discard", discarded.len()))?;
+        for city in discarded {
+            writeln!(f, "\t{}", city)?;
+        }
+        Ok(())
+    }
+}
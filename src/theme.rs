@@ -0,0 +1,157 @@
+use std::{
+    fmt::Display,
+    io::{stdout, IsTerminal},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crossterm::style::{Color as ConsoleColor, ContentStyle, StyledContent};
+
+use crate::common::Color;
+
+/// How colors are drawn: the original palette, a brighter one for dark or washed-out screens,
+/// one that stays distinct under common color blindness, or no escape codes at all.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Theme {
+    Standard,
+    HighContrast,
+    ColorBlind,
+    Plain,
+}
+
+/// Index into `Theme::ALL` of the active theme.
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+
+impl Theme {
+    pub const ALL: [Theme; 4] = [
+        Theme::Standard,
+        Theme::HighContrast,
+        Theme::ColorBlind,
+        Theme::Plain,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Standard => "standard",
+            Self::HighContrast => "high-contrast",
+            Self::ColorBlind => "color-blind",
+            Self::Plain => "plain",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|theme| theme.name() == name.trim().to_lowercase())
+    }
+
+    /// Uses `--theme <name>` when given, then `PANDEMIC_THEME` when it names a theme, and
+    /// otherwise falls back to plain when `NO_COLOR` is set or stdout is piped, so logs stay
+    /// free of escape codes.
+    pub fn detect() -> Self {
+        let args: Vec<String> = std::env::args().collect();
+        for (i, arg) in args.iter().enumerate() {
+            let name = match arg.strip_prefix("--theme=") {
+                Some(name) => Some(name),
+                None if arg == "--theme" => args.get(i + 1).map(|name| name.as_str()),
+                None => None,
+            };
+            if let Some(theme) = name.and_then(Self::from_name) {
+                return theme;
+            }
+        }
+        if let Some(theme) = std::env::var("PANDEMIC_THEME")
+            .ok()
+            .and_then(|name| Self::from_name(&name))
+        {
+            return theme;
+        }
+        let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+        if no_color || !stdout().is_terminal() {
+            Self::Plain
+        } else {
+            Self::Standard
+        }
+    }
+
+    pub fn current() -> Self {
+        Self::ALL[CURRENT.load(Ordering::Relaxed)]
+    }
+
+    pub fn apply(self) {
+        let idx = Self::ALL
+            .iter()
+            .position(|theme| *theme == self)
+            .unwrap_or(0);
+        CURRENT.store(idx, Ordering::Relaxed);
+    }
+
+    pub fn disease_color(&self, color: Color) -> ConsoleColor {
+        match self {
+            Self::Standard | Self::Plain => match color {
+                Color::Yellow => ConsoleColor::Yellow,
+                Color::Black => ConsoleColor::DarkMagenta,
+                Color::Blue => ConsoleColor::Blue,
+                Color::Red => ConsoleColor::Red,
+            },
+            Self::HighContrast => match color {
+                Color::Yellow => ConsoleColor::Yellow,
+                Color::Black => ConsoleColor::White,
+                Color::Blue => ConsoleColor::Cyan,
+                Color::Red => ConsoleColor::Red,
+            },
+            // Okabe-Ito colors, which stay apart for protanopia, deuteranopia and tritanopia.
+            Self::ColorBlind => match color {
+                Color::Yellow => ConsoleColor::Rgb {
+                    r: 240,
+                    g: 228,
+                    b: 66,
+                },
+                Color::Black => ConsoleColor::Rgb {
+                    r: 204,
+                    g: 121,
+                    b: 167,
+                },
+                Color::Blue => ConsoleColor::Rgb {
+                    r: 86,
+                    g: 180,
+                    b: 233,
+                },
+                Color::Red => ConsoleColor::Rgb {
+                    r: 213,
+                    g: 94,
+                    b: 0,
+                },
+            },
+        }
+    }
+
+    /// Whether colors need a letter next to them to be told apart, on the map and wherever a
+    /// color is named.
+    pub fn uses_letters(&self) -> bool {
+        matches!(self, Self::ColorBlind | Self::Plain)
+    }
+
+    pub fn uses_color(&self) -> bool {
+        *self != Self::Plain
+    }
+}
+
+impl std::fmt::Display for Theme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Drops the styling from styled text while the plain theme is active.
+pub trait Themed {
+    fn themed(self) -> Self;
+}
+
+impl<D: Display> Themed for StyledContent<D> {
+    fn themed(mut self) -> Self {
+        if !Theme::current().uses_color() {
+            *self.style_mut() = ContentStyle::new();
+        }
+        self
+    }
+}
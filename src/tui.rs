@@ -0,0 +1,662 @@
+use std::io::{self, stdout, Write};
+
+use crossterm::{
+    cursor::{Hide, MoveTo, Show},
+    event::{read, Event as TerminalEvent, KeyCode, KeyEventKind, KeyModifiers},
+    execute, queue,
+    style::{Color as ConsoleColor, Print, ResetColor, SetForegroundColor},
+    terminal::{
+        disable_raw_mode, enable_raw_mode, size, Clear, ClearType, EnterAlternateScreen,
+        LeaveAlternateScreen,
+    },
+};
+
+use crate::{
+    board::{self, Board, Cities},
+    cli::Options,
+    command::Command,
+    common::Color,
+    game::{self, Game, Phase},
+    i18n::tr,
+    menu::strip_ansi,
+    player::{self, Player},
+    player_card::PlayerCard,
+    setup,
+    theme::Theme,
+};
+
+/// Width of the grid the city positions below are laid out on.
+const MAP_WIDTH: u16 = 125;
+const PANEL_WIDTH: u16 = 34;
+/// Height of the map sent to spectators, whose screens this side cannot measure.
+const SNAPSHOT_HEIGHT: u16 = 30;
+/// Rows taken by the map in the full-screen game; the hands and actions go below it.
+const MAP_HEIGHT: u16 = 28;
+
+/// Where each city sits on the map grid, with the short code used as its label.
+fn layout(city: Cities) -> (u16, u16, &'static str) {
+    match city {
+        Cities::SanFrancisco => (3, 7, "SFO"),
+        Cities::Chicago => (14, 6, "CHI"),
+        Cities::Montreal => (24, 5, "MON"),
+        Cities::NewYork => (33, 6, "NYC"),
+        Cities::Washington => (31, 10, "WAS"),
+        Cities::Atlanta => (19, 10, "ATL"),
+        Cities::London => (50, 4, "LON"),
+        Cities::Essen => (59, 3, "ESS"),
+        Cities::SaintPetersburg => (70, 2, "STP"),
+        Cities::Madrid => (48, 9, "MAD"),
+        Cities::Paris => (57, 7, "PAR"),
+        Cities::Milan => (65, 5, "MIL"),
+        Cities::LosAngeles => (4, 12, "LAX"),
+        Cities::MexicoCity => (14, 14, "MEX"),
+        Cities::Miami => (25, 13, "MIA"),
+        Cities::Bogota => (25, 17, "BOG"),
+        Cities::Lima => (21, 21, "LIM"),
+        Cities::Santiago => (22, 25, "SCL"),
+        Cities::BuenosAires => (33, 25, "BUE"),
+        Cities::SaoPaulo => (38, 21, "SAO"),
+        Cities::Lagos => (55, 16, "LAG"),
+        Cities::Kinshasa => (60, 20, "KIN"),
+        Cities::Khartoum => (68, 16, "KHA"),
+        Cities::Johannesburg => (66, 24, "JNB"),
+        Cities::Algiers => (57, 12, "ALG"),
+        Cities::Cairo => (66, 12, "CAI"),
+        Cities::Istanbul => (68, 8, "IST"),
+        Cities::Moscow => (77, 5, "MOS"),
+        Cities::Baghdad => (76, 10, "BAG"),
+        Cities::Riyadh => (77, 14, "RIY"),
+        Cities::Tehran => (85, 7, "THR"),
+        Cities::Karachi => (86, 11, "KAR"),
+        Cities::Delhi => (93, 9, "DEL"),
+        Cities::Mumbai => (87, 15, "MUM"),
+        Cities::Chennai => (94, 18, "CHE"),
+        Cities::Kolkata => (100, 10, "KOL"),
+        Cities::Beijing => (103, 4, "BEI"),
+        Cities::Seoul => (111, 4, "SEO"),
+        Cities::Tokyo => (119, 7, "TYO"),
+        Cities::Shanghai => (104, 8, "SHA"),
+        Cities::Osaka => (119, 11, "OSA"),
+        Cities::Taipei => (112, 12, "TPE"),
+        Cities::HongKong => (104, 13, "HKG"),
+        Cities::Bangkok => (100, 16, "BKK"),
+        Cities::HoChiMinhCity => (106, 19, "HCM"),
+        Cities::Manila => (114, 18, "MNL"),
+        Cities::Jakarta => (100, 22, "JAK"),
+        Cities::Sydney => (116, 25, "SYD"),
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Cell {
+    ch: char,
+    color: ConsoleColor,
+}
+
+/// A grid of colored characters, flushed to the terminal in one go.
+struct Canvas {
+    width: u16,
+    height: u16,
+    cells: Vec<Cell>,
+}
+
+impl Canvas {
+    fn new(width: u16, height: u16) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![
+                Cell {
+                    ch: ' ',
+                    color: ConsoleColor::Reset
+                };
+                width as usize * height as usize
+            ],
+        }
+    }
+
+    fn put(&mut self, x: i32, y: i32, ch: char, color: ConsoleColor) {
+        if x >= 0 && y >= 0 && (x as u16) < self.width && (y as u16) < self.height {
+            self.cells[y as usize * self.width as usize + x as usize] = Cell { ch, color };
+        }
+    }
+
+    fn text(&mut self, x: i32, y: i32, text: &str, color: ConsoleColor) {
+        for (i, ch) in text.chars().enumerate() {
+            self.put(x + i as i32, y, ch, color);
+        }
+    }
+
+    /// Like `text`, but cut off after `width` characters so it stays in its column.
+    fn clipped(&mut self, x: i32, y: i32, width: u16, text: &str, color: ConsoleColor) {
+        let text: String = text.chars().take(width as usize).collect();
+        self.text(x, y, &text, color);
+    }
+
+    fn line(&mut self, from: (i32, i32), to: (i32, i32)) {
+        let (mut x, mut y) = from;
+        let (dx, dy) = ((to.0 - x).abs(), -(to.1 - y).abs());
+        let (sx, sy) = (if x < to.0 { 1 } else { -1 }, if y < to.1 { 1 } else { -1 });
+        let mut err = dx + dy;
+        loop {
+            self.put(x, y, '·', ConsoleColor::DarkGrey);
+            if (x, y) == to {
+                break;
+            }
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y += sy;
+            }
+        }
+    }
+
+    fn flush(&self, out: &mut impl Write) -> std::io::Result<()> {
+        for y in 0..self.height {
+            queue!(out, MoveTo(0, y))?;
+            self.write_row(out, y)?;
+        }
+        Ok(())
+    }
+
+    /// The canvas as lines of text without cursor movement, for terminals elsewhere.
+    fn lines(&self) -> String {
+        let mut out = Vec::new();
+        for y in 0..self.height {
+            // Writing to memory cannot fail.
+            let _ = self.write_row(&mut out, y);
+            out.push(b'\n');
+        }
+        String::from_utf8_lossy(&out).into_owned()
+    }
+
+    /// One row up to its last mark, switching colors only where they change.
+    fn write_row(&self, out: &mut impl Write, y: u16) -> std::io::Result<()> {
+        let colored = Theme::current().uses_color();
+        let row = &self.cells[y as usize * self.width as usize..][..self.width as usize];
+        let end = row
+            .iter()
+            .rposition(|cell| cell.ch != ' ')
+            .map_or(0, |x| x + 1);
+        let mut current = None;
+        for cell in &row[..end] {
+            if colored && current != Some(cell.color) {
+                queue!(out, SetForegroundColor(cell.color))?;
+                current = Some(cell.color);
+            }
+            queue!(out, Print(cell.ch))?;
+        }
+        if colored {
+            queue!(out, ResetColor)?;
+        }
+        Ok(())
+    }
+}
+
+fn draw_map(canvas: &mut Canvas, board: &Board, players: &[&Player], width: u16) {
+    let scale = |city: Cities| {
+        let (x, y, _) = layout(city);
+        (
+            (x as u32 * width as u32 / MAP_WIDTH as u32) as i32,
+            y as i32 + 1,
+        )
+    };
+
+    let mut cities = board.all_cities();
+    cities.sort_unstable();
+    for city in &cities {
+        for adjacent in board.adjacent_to(*city).unwrap_or_default() {
+            if adjacent < *city {
+                continue;
+            }
+            let (from, to) = (scale(*city), scale(adjacent));
+            if (from.0 - to.0).abs() > width as i32 / 2 {
+                // Routes across the Pacific leave one edge of the map and enter the other.
+                let (west, east) = if from.0 < to.0 {
+                    (from, to)
+                } else {
+                    (to, from)
+                };
+                let middle = (west.1 + east.1) / 2;
+                canvas.line(west, (0, middle));
+                canvas.line(east, (width as i32 - 1, middle));
+            } else {
+                canvas.line(from, to);
+            }
+        }
+    }
+
+    for city in &cities {
+        let (x, y) = scale(*city);
+        let city_obj = board.map.get(city).unwrap();
+        let (_, _, code) = layout(*city);
+        canvas.text(x, y, code, city_obj.color.console_color());
+        if city_obj.has_research_station {
+            canvas.put(x + code.len() as i32, y, '■', ConsoleColor::White);
+        }
+
+        let mut offset = 0;
+        for color in [Color::Blue, Color::Yellow, Color::Black, Color::Red] {
+            let count = city_obj.infection_count(color);
+            if count > 0 {
+                let ch = char::from_digit(count as u32, 10).unwrap_or('#');
+                canvas.put(x + offset, y + 1, ch, color.console_color());
+                offset += 1;
+                if Theme::current().uses_letters() {
+                    canvas.put(x + offset, y + 1, color.letter(), color.console_color());
+                    offset += 1;
+                }
+            }
+        }
+        for player in players.iter().filter(|player| player.location() == *city) {
+            canvas.put(x + offset, y + 1, player.initial(), player.role().color());
+            offset += 1;
+        }
+    }
+}
+
+fn draw_panel(canvas: &mut Canvas, board: &Board, players: &[&Player], left: i32) {
+    let mut y = 1;
+    let mut row = |canvas: &mut Canvas, text: &str, color: ConsoleColor| {
+        canvas.text(left, y, text, color);
+        y += 1;
+    };
+
+    row(canvas, "PANDEMIC", ConsoleColor::White);
+    row(canvas, "", ConsoleColor::Reset);
+    row(
+        canvas,
+        &tr!("map.outbreaks", board.outbreaks, board::MAX_OUTBREAKS),
+        if board.outbreaks + 2 >= board::MAX_OUTBREAKS {
+            ConsoleColor::Red
+        } else {
+            ConsoleColor::Reset
+        },
+    );
+    let track: Vec<String> = board::INFECTION_RATE
+        .iter()
+        .enumerate()
+        .map(|(i, rate)| {
+            if i == board.infection_rate_position() {
+                format!("[{}]", rate)
+            } else {
+                rate.to_string()
+            }
+        })
+        .collect();
+    row(
+        canvas,
+        &tr!("status.infection_rate", track.join(" ")),
+        ConsoleColor::Reset,
+    );
+    row(
+        canvas,
+        &tr!("map.epidemics_left", board.epidemics_remaining()),
+        ConsoleColor::Reset,
+    );
+    row(canvas, "", ConsoleColor::Reset);
+    row(canvas, &tr!("map.disease_header"), ConsoleColor::Reset);
+    for color in [Color::Blue, Color::Yellow, Color::Black, Color::Red] {
+        row(
+            canvas,
+            &format!(
+                "{:<8} {:>2}/{}  {}",
+                color.name(),
+                board::MAX_INFECTION_PER_TYPE - board.total_cubes(color),
+                board::MAX_INFECTION_PER_TYPE,
+                board.disease_state(color)
+            ),
+            color.console_color(),
+        );
+    }
+    row(canvas, "", ConsoleColor::Reset);
+    for (label, count) in [
+        (tr!("map.player_deck"), board.player_deck.len()),
+        (tr!("map.player_discard"), board.player_discard.len()),
+        (tr!("map.infection_deck"), board.infection_deck.len()),
+        (tr!("map.infection_discard"), board.infection_discard.len()),
+    ] {
+        row(
+            canvas,
+            &format!("{:<18} {:>2}", label, count),
+            ConsoleColor::Reset,
+        );
+    }
+    row(
+        canvas,
+        &format!(
+            "{:<18} {}/{}",
+            tr!("map.stations"),
+            board.total_research_stations(),
+            board::MAX_RESEARCH_STATIONS
+        ),
+        ConsoleColor::Reset,
+    );
+    row(canvas, "", ConsoleColor::Reset);
+    for player in players {
+        row(
+            canvas,
+            &tr!(
+                "map.player",
+                player.initial(),
+                player.plain_name(),
+                player.hand.len()
+            ),
+            player.role().color(),
+        );
+    }
+}
+
+/// Draws the whole board full-screen until a key is pressed.
+pub fn show_world_map(board: &Board, player: &Player, others: &[Player]) -> std::io::Result<()> {
+    let mut players = vec![player];
+    players.extend(others.iter());
+
+    let mut out = stdout();
+    let _screen = FullScreen::enter()?;
+    loop {
+        let (width, height) = size()?;
+        let map_width = width.saturating_sub(PANEL_WIDTH);
+        let mut canvas = Canvas::new(width, height);
+        draw_map(&mut canvas, board, &players, map_width);
+        draw_panel(&mut canvas, board, &players, map_width as i32 + 1);
+        canvas.text(
+            0,
+            height as i32 - 1,
+            &tr!("map.press_any_key"),
+            ConsoleColor::DarkGrey,
+        );
+        queue!(out, Clear(ClearType::All))?;
+        canvas.flush(&mut out)?;
+        out.flush()?;
+        match read()? {
+            TerminalEvent::Key(_) => return Ok(()),
+            _ => continue,
+        }
+    }
+}
+
+/// What the full-screen game shows under the map besides the board itself.
+struct Screen {
+    actions: Vec<Command>,
+    selected: usize,
+    /// The first action shown, once the list is longer than its space.
+    scroll: usize,
+    message: Option<(String, ConsoleColor)>,
+}
+
+/// Whose answer the game waits for and what it is, or how the game ended.
+fn prompt(game: &Game) -> String {
+    match game.phase {
+        Phase::Actions => format!(
+            "{} {}",
+            game.players[game.current].plain_name(),
+            tr!(
+                "turn.take_turn",
+                player::ACTIONS_PER_TURN - game.actions_left,
+                player::ACTIONS_PER_TURN
+            )
+        ),
+        Phase::Offer { to, card, giving } => {
+            let key = if giving {
+                "net.offer_give"
+            } else {
+                "net.offer_take"
+            };
+            format!(
+                "{}: {}",
+                game.players[to].plain_name(),
+                tr!(key, game.players[game.current].plain_name(), card.name())
+            )
+        }
+        Phase::Discard { player, .. } => format!(
+            "{}: {}",
+            game.players[player].plain_name(),
+            tr!(
+                "tui.discard",
+                game.players[player].hand.len(),
+                player::MAX_CARDS_IN_HAND
+            )
+        ),
+        Phase::Over(outcome) => outcome.to_string(),
+    }
+}
+
+/// Every hand, one player to a line, with City cards in their disease color.
+fn draw_hands(canvas: &mut Canvas, game: &Game, left: i32, top: i32, width: u16) -> i32 {
+    let mut y = top;
+    canvas.clipped(left, y, width, &tr!("tui.hands"), ConsoleColor::White);
+    for player in &game.players {
+        y += 1;
+        let right = left + width as i32;
+        let mut x = left;
+        let mut write = |canvas: &mut Canvas, text: &str, color: ConsoleColor| {
+            let room = (right - x).max(0) as u16;
+            canvas.clipped(x, y, room, text, color);
+            x += text.chars().count() as i32;
+        };
+        write(
+            canvas,
+            &strip_ansi(&format!("{} ({}): ", player.plain_name(), player.role())),
+            player.role().color(),
+        );
+        for (i, card) in player.hand.iter().enumerate() {
+            if i > 0 {
+                write(canvas, ", ", ConsoleColor::Reset);
+            }
+            match card {
+                PlayerCard::CityCard(city) => {
+                    let mut name = city.city.name().to_owned();
+                    if Theme::current().uses_letters() {
+                        name.push_str(&format!(" {}", city.color.letter()));
+                    }
+                    write(canvas, &name, city.color.console_color())
+                }
+                PlayerCard::EventCard(event) => write(
+                    canvas,
+                    &strip_ansi(&event.event().to_string()),
+                    ConsoleColor::White,
+                ),
+                PlayerCard::EpidemicCard => write(canvas, "?", ConsoleColor::Reset),
+            }
+        }
+    }
+    y + 1
+}
+
+/// The legal actions, with the chosen one marked, scrolled to keep it in view.
+fn draw_actions(
+    canvas: &mut Canvas,
+    screen: &mut Screen,
+    left: i32,
+    top: i32,
+    width: u16,
+    rows: usize,
+) {
+    if rows == 0 {
+        return;
+    }
+    if screen.selected < screen.scroll {
+        screen.scroll = screen.selected;
+    } else if screen.selected >= screen.scroll + rows {
+        screen.scroll = screen.selected + 1 - rows;
+    }
+    for (row, (i, action)) in screen
+        .actions
+        .iter()
+        .enumerate()
+        .skip(screen.scroll)
+        .take(rows)
+        .enumerate()
+    {
+        let (marker, color) = if i == screen.selected {
+            ('>', ConsoleColor::White)
+        } else {
+            (' ', ConsoleColor::Grey)
+        };
+        canvas.clipped(
+            left,
+            top + row as i32,
+            width,
+            &format!("{} {}", marker, action),
+            color,
+        );
+    }
+}
+
+/// The last entries of the game log, newest at the bottom.
+fn draw_log(canvas: &mut Canvas, board: &Board, left: i32, top: i32, width: u16, rows: usize) {
+    let entries = board.log.entries();
+    let shown = &entries[entries.len().saturating_sub(rows)..];
+    for (row, entry) in shown.iter().enumerate() {
+        canvas.clipped(
+            left,
+            top + row as i32,
+            width,
+            &strip_ansi(&entry.to_string()),
+            ConsoleColor::DarkGrey,
+        );
+    }
+}
+
+fn draw_game(canvas: &mut Canvas, game: &Game, screen: &mut Screen) {
+    let (width, height) = (canvas.width, canvas.height);
+    let map_width = width.saturating_sub(PANEL_WIDTH);
+    let players: Vec<&Player> = game.players.iter().collect();
+    draw_map(canvas, &game.board, &players, map_width);
+    draw_panel(canvas, &game.board, &players, map_width as i32 + 1);
+
+    let top = MAP_HEIGHT as i32;
+    let column = map_width / 2;
+    let bottom = height as i32 - 1;
+    canvas.clipped(0, top, map_width, &prompt(game), ConsoleColor::White);
+    draw_actions(
+        canvas,
+        screen,
+        0,
+        top + 1,
+        column,
+        (bottom - top - 1).max(0) as usize,
+    );
+    let below_hands = draw_hands(
+        canvas,
+        game,
+        column as i32 + 1,
+        top,
+        width.saturating_sub(column + 1),
+    );
+    draw_log(
+        canvas,
+        &game.board,
+        column as i32 + 1,
+        below_hands + 1,
+        width.saturating_sub(column + 1),
+        (bottom - below_hands - 1).max(0) as usize,
+    );
+    let (text, color) = screen
+        .message
+        .clone()
+        .unwrap_or_else(|| (tr!("tui.keys"), ConsoleColor::DarkGrey));
+    canvas.clipped(0, bottom, width, &text, color);
+}
+
+/// Raw mode and the alternate screen, handed back to the terminal when dropped, so a panic
+/// does not leave it unusable.
+pub(crate) struct FullScreen;
+
+impl FullScreen {
+    pub(crate) fn enter() -> io::Result<Self> {
+        enable_raw_mode()?;
+        let screen = Self;
+        execute!(stdout(), EnterAlternateScreen, Hide)?;
+        Ok(screen)
+    }
+}
+
+impl Drop for FullScreen {
+    fn drop(&mut self) {
+        let _ = execute!(stdout(), Show, LeaveAlternateScreen);
+        let _ = disable_raw_mode();
+    }
+}
+
+/// Plays a whole game full-screen: the map and board stay in view above every hand, and the
+/// legal actions are picked with the arrow keys and Enter.
+pub fn play(options: &Options) -> io::Result<()> {
+    let mut game = Game::new(&setup::prompt_setup(options));
+    // Everything the game would print shows up in the log on screen instead.
+    game::set_quiet(true);
+
+    let mut screen = Screen {
+        actions: game.legal_actions(),
+        selected: 0,
+        scroll: 0,
+        message: None,
+    };
+    let mut out = stdout();
+    let _screen = FullScreen::enter()?;
+    loop {
+        let (width, height) = size()?;
+        let mut canvas = Canvas::new(width, height);
+        draw_game(&mut canvas, &game, &mut screen);
+        queue!(out, Clear(ClearType::All))?;
+        canvas.flush(&mut out)?;
+        out.flush()?;
+
+        let TerminalEvent::Key(key) = read()? else {
+            continue;
+        };
+        if key.kind == KeyEventKind::Release {
+            continue;
+        }
+        if matches!(game.phase, Phase::Over(_)) {
+            return Ok(());
+        }
+        let last = screen.actions.len().saturating_sub(1);
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
+            KeyCode::Esc => return Ok(()),
+            KeyCode::Up => screen.selected = screen.selected.saturating_sub(1),
+            KeyCode::Down => screen.selected = (screen.selected + 1).min(last),
+            KeyCode::PageUp => screen.selected = screen.selected.saturating_sub(10),
+            KeyCode::PageDown => screen.selected = (screen.selected + 10).min(last),
+            KeyCode::Home => screen.selected = 0,
+            KeyCode::End => screen.selected = last,
+            KeyCode::Enter => {
+                let (Some(seat), Some(command)) =
+                    (game.waiting_on(), screen.actions.get(screen.selected))
+                else {
+                    continue;
+                };
+                screen.message = match game.apply(seat, command) {
+                    Ok(()) => {
+                        screen.actions = game.legal_actions();
+                        screen.selected = 0;
+                        screen.scroll = 0;
+                        match game.phase {
+                            Phase::Over(_) => Some((tr!("tui.game_over"), ConsoleColor::White)),
+                            _ => None,
+                        }
+                    }
+                    Err(message) => Some((
+                        format!("{}: {}", tr!("error"), strip_ansi(&message)),
+                        ConsoleColor::Red,
+                    )),
+                };
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The map screen as text at a fixed size, for spectators.
+pub fn render_world_map(board: &Board, players: &[&Player]) -> String {
+    let mut canvas = Canvas::new(MAP_WIDTH + PANEL_WIDTH, SNAPSHOT_HEIGHT);
+    draw_map(&mut canvas, board, players, MAP_WIDTH);
+    draw_panel(&mut canvas, board, players, MAP_WIDTH as i32 + 1);
+    canvas.lines()
+}
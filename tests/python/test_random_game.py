@@ -0,0 +1,70 @@
+"""Plays seeded random games through the Python bindings.
+
+Build the module first with `maturin develop`, then run `python -m pytest tests/python` or
+this file directly.
+"""
+
+import random
+
+import pandemic_cli
+
+
+def play(seed):
+    game = pandemic_cli.Game(["Ana:Medic", "Bo", "Cy"], difficulty="introductory", seed=seed)
+    chooser = random.Random(seed)
+    turns = []
+    while not game.over:
+        actions = game.legal_actions()
+        assert actions, "a game that is not over always has something to do"
+        assert [action["id"] for action in actions] == list(range(len(actions)))
+        choice = chooser.randrange(len(actions))
+        turns.append(actions[choice]["command"])
+        game.apply(choice)
+    return game, turns
+
+
+def test_seeded_random_game_plays_to_the_end():
+    game, turns = play(7)
+    assert game.outcome in ("victory", "outbreaks", "cubes", "cards")
+    assert game.waiting_on is None
+    state = game.state()
+    assert state["phase"] == "over"
+    assert len(state["cities"]) == 48
+    assert sorted(player["name"] for player in state["players"]) == ["Ana", "Bo", "Cy"]
+
+    # The same seed deals and plays out the same way.
+    again, replayed = play(7)
+    assert replayed == turns
+    assert again.state() == state
+
+
+def test_illegal_moves_raise():
+    game = pandemic_cli.Game(["Ana", "Bo"], seed=1)
+    try:
+        game.apply(len(game.legal_actions()))
+    except ValueError:
+        pass
+    else:
+        raise AssertionError("an action past the end of the list was accepted")
+
+
+def test_env_masks_its_actions():
+    env = pandemic_cli.Env(players=2)
+    observation = env.reset(3)
+    assert len(observation) == pandemic_cli.OBSERVATION_SIZE
+    chooser = random.Random(3)
+    done = False
+    while not done:
+        mask = env.action_mask()
+        assert len(mask) == pandemic_cli.ACTIONS
+        legal = [index for index, allowed in enumerate(mask) if allowed]
+        observation, reward, done = env.step(chooser.choice(legal))
+        assert len(observation) == pandemic_cli.OBSERVATION_SIZE
+    assert reward in (1.0, -1.0)
+
+
+if __name__ == "__main__":
+    test_seeded_random_game_plays_to_the_end()
+    test_illegal_moves_raise()
+    test_env_masks_its_actions()
+    print("ok")